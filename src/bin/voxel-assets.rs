@@ -0,0 +1,38 @@
+use std::{path::PathBuf, process::ExitCode};
+
+use voxel_game_02::block::{asset_diagnostics::AssetDiagnostics, asset_loader::load_assets, resource_pack::{ResourcePackStack, BASE_RESOURCE_PACK_PATH}};
+
+const USAGE: &str = "usage: voxel-assets check [assets directory] [resource packs...]";
+
+// loads the assets the same way the game does, without a window or a gpu
+fn check(assets_path: &PathBuf, resource_pack_paths: &[PathBuf]) -> anyhow::Result<AssetDiagnostics> {
+    let resource_packs = ResourcePackStack::new(assets_path, resource_pack_paths)?;
+    let mut diagnostics = AssetDiagnostics::new();
+    load_assets(&resource_packs, None, &mut diagnostics)?;
+    Ok(diagnostics)
+}
+
+fn main() -> ExitCode {
+    let mut args = std::env::args().skip(1);
+    if args.next().as_deref() != Some("check") {
+        eprintln!("{}", USAGE);
+        return ExitCode::FAILURE;
+    }
+    let assets_path = args.next().map_or_else(|| PathBuf::from(BASE_RESOURCE_PACK_PATH), PathBuf::from);
+    let resource_pack_paths = args.map(PathBuf::from).collect::<Vec<PathBuf>>();
+
+    let diagnostics = match check(&assets_path, &resource_pack_paths) {
+        Ok(diagnostics) => diagnostics,
+        Err(err) => {
+            eprintln!("error: {}", err);
+            return ExitCode::FAILURE;
+        }
+    };
+
+    for problem in diagnostics.problems() {
+        println!("{}", problem);
+    }
+    println!("{} errors, {} warnings", diagnostics.errors_num(), diagnostics.warnings_num());
+
+    if diagnostics.errors_num() > 0 { ExitCode::FAILURE } else { ExitCode::SUCCESS }
+}
@@ -0,0 +1,40 @@
+use std::{path::PathBuf, process::ExitCode};
+
+use voxel_game_02::{block::{asset_diagnostics::AssetDiagnostics, asset_loader::load_assets, resource_pack::{ResourcePackStack, BASE_RESOURCE_PACK_PATH}}, render_regions, set_assets, REGIONS_SAVE_DIRECTORY};
+
+const USAGE: &str = "usage: voxel-map <output.png> [regions directory] [assets directory] [resource packs...]";
+
+// renders the saved regions from above without a window or a gpu, the block colors come from the resource packs
+fn render(output_path: &PathBuf, regions_directory: &PathBuf, assets_path: &PathBuf, resource_pack_paths: &[PathBuf]) -> anyhow::Result<()> {
+    let resource_packs = ResourcePackStack::new(assets_path, resource_pack_paths)?;
+    let mut diagnostics = AssetDiagnostics::new();
+    set_assets(load_assets(&resource_packs, None, &mut diagnostics)?);
+    if diagnostics.errors_num() > 0 {
+        for problem in diagnostics.problems() {
+            eprintln!("{}", problem);
+        }
+        anyhow::bail!("the assets have {} errors", diagnostics.errors_num());
+    }
+
+    let image = render_regions(regions_directory)?;
+    image.save(output_path)?;
+    println!("rendered {}x{} blocks to {}", image.width(), image.height(), output_path.display());
+    Ok(())
+}
+
+fn main() -> ExitCode {
+    let mut args = std::env::args().skip(1);
+    let Some(output_path) = args.next().map(PathBuf::from) else {
+        eprintln!("{}", USAGE);
+        return ExitCode::FAILURE;
+    };
+    let regions_directory = args.next().map_or_else(|| PathBuf::from(REGIONS_SAVE_DIRECTORY), PathBuf::from);
+    let assets_path = args.next().map_or_else(|| PathBuf::from(BASE_RESOURCE_PACK_PATH), PathBuf::from);
+    let resource_pack_paths = args.map(PathBuf::from).collect::<Vec<PathBuf>>();
+
+    if let Err(err) = render(&output_path, &regions_directory, &assets_path, &resource_pack_paths) {
+        eprintln!("error: {}", err);
+        return ExitCode::FAILURE;
+    }
+    ExitCode::SUCCESS
+}
@@ -0,0 +1,93 @@
+use std::path::{Path, PathBuf};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AssetProblemSeverity {
+    // the asset was loaded with a fallback
+    Warning,
+    // the asset couldn't be loaded
+    Error,
+}
+
+#[derive(Debug, Clone)]
+pub struct AssetProblem {
+    pub severity: AssetProblemSeverity,
+    pub file: PathBuf,
+    // path of the offending field inside the file e.g. "variants[1].model"
+    pub field: Option<String>,
+    pub message: String,
+}
+
+impl std::fmt::Display for AssetProblem {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let severity = match self.severity {
+            AssetProblemSeverity::Warning => "warning",
+            AssetProblemSeverity::Error => "error",
+        };
+        write!(f, "{}: {}", severity, self.file.display())?;
+        if let Some(field) = &self.field {
+            write!(f, ": {}", field)?;
+        }
+        write!(f, ": {}", self.message)
+    }
+}
+
+// Problems found while loading the assets, the loaders skip whatever is broken and keep going
+// so that every problem gets reported at once
+#[derive(Debug, Clone, Default)]
+pub struct AssetDiagnostics {
+    problems: Vec<AssetProblem>,
+}
+
+impl AssetDiagnostics {
+    pub fn new() -> Self {
+        Self { problems: vec![] }
+    }
+
+    pub fn error<P: AsRef<Path>, M: ToString>(&mut self, file: P, field: Option<String>, message: M) {
+        self.push(AssetProblemSeverity::Error, file, field, message);
+    }
+
+    pub fn warning<P: AsRef<Path>, M: ToString>(&mut self, file: P, field: Option<String>, message: M) {
+        self.push(AssetProblemSeverity::Warning, file, field, message);
+    }
+
+    fn push<P: AsRef<Path>, M: ToString>(&mut self, severity: AssetProblemSeverity, file: P, field: Option<String>, message: M) {
+        self.problems.push(AssetProblem { severity, file: file.as_ref().to_path_buf(), field, message: message.to_string() });
+    }
+
+    pub fn problems(&self) -> &[AssetProblem] {
+        &self.problems
+    }
+
+    pub fn errors_num(&self) -> usize {
+        self.problems.iter().filter(|problem| problem.severity == AssetProblemSeverity::Error).count()
+    }
+
+    pub fn warnings_num(&self) -> usize {
+        self.problems.iter().filter(|problem| problem.severity == AssetProblemSeverity::Warning).count()
+    }
+
+    // runs a loader, prints its warnings and fails with every error it reported
+    pub fn load<T>(load: impl FnOnce(&mut Self) -> anyhow::Result<T>) -> anyhow::Result<T> {
+        let mut diagnostics = Self::new();
+        let value = load(&mut diagnostics)?;
+        diagnostics.into_result(value)
+    }
+
+    // prints the warnings and fails with every error if there are any
+    pub fn into_result<T>(self, value: T) -> anyhow::Result<T> {
+        for warning in self.problems.iter().filter(|problem| problem.severity == AssetProblemSeverity::Warning) {
+            println!("{}", warning);
+        }
+
+        if self.errors_num() > 0 {
+            let errors = self.problems.iter()
+                .filter(|problem| problem.severity == AssetProblemSeverity::Error)
+                .map(|problem| problem.to_string())
+                .collect::<Vec<String>>();
+            anyhow::bail!("failed to load assets:\n{}", errors.join("\n"));
+        }
+
+        Ok(value)
+    }
+}
@@ -1,106 +1,297 @@
 use std::{collections::HashMap, io::Read, ops::Index};
 
-use cgmath::{Deg, Rotation, Vector3, Zero};
+use cgmath::{Vector3, Zero};
 
 use crate::{block::{model::{block_model_variant::BlockModelVariant, quad_block_model::QuadIndexBlockModel}, FACE_DIRECTIONS_NUM}, collision::bounding_box::LocalBoundingBox};
 
-use super::{model::{block_model_variant::BlockModelVariants, cuboid_block_model::{CuboidBlockModel, CuboidBlockModelDeserialize, DeserializedCuboidModels}, quad_block_model::QuadBlockModel, BlockDeserialize, QuadRaw}, BlockId, BlockInformation, Properties, PropertiesOptional};
-const TEXTURE_ATLAS_HEIGHT_IN_BLOCKS: u32 = 16;
-const TEXTURE_ATLAS_WIDTH_IN_BLOCKS: u32 = 16;
-const TEXTURE_ATLAS_UV_HEIGHT_PER_BLOCK: f32 = 1.0 / TEXTURE_ATLAS_HEIGHT_IN_BLOCKS as f32;
-const TEXTURE_ATLAS_UV_WIDTH_PER_BLOCK: f32 = 1.0 / TEXTURE_ATLAS_WIDTH_IN_BLOCKS as f32;
+use super::{asset_diagnostics::AssetDiagnostics, block_state::Value, rotation::BLOCK_CENTER, resource_pack::{AssetFile, ResourcePackStack}, texture_animation::{TextureAnimation, TextureAnimationDeserialize, TextureAnimations}, model::{block_model_variant::BlockModelVariants, cuboid_block_model::{CuboidBlockModel, CuboidBlockModelDeserialize, DeserializedCuboidModels}, quad_block_model::QuadBlockModel, BlockDeserialize, QuadRaw}, BlockId, BlockInformation, Properties};
 
-pub fn load_models<T: Into<std::path::PathBuf>>(path: T) -> anyhow::Result<BaseCuboidBlockModels> {
-    // load directory
-    let path: std::path::PathBuf = path.into();
-    let dir = path.read_dir()?;
+pub const MISSING_TEXTURE_NAME: &str = "missing";
 
+// magenta and black checkerboard used for textures that couldn't be found
+fn missing_texture_image(width: u32, height: u32) -> image::RgbaImage {
+    image::RgbaImage::from_fn(width, height, |x, y| {
+        if (x * 2 / width + y * 2 / height) % 2 == 0 {
+            image::Rgba([255, 0, 255, 255])
+        } else {
+            image::Rgba([0, 0, 0, 255])
+        }
+    })
+}
+
+// loads every png in the textures directory of the resource packs and its subdirectories
+// a texture is named after its path relative to the directory without the extension e.g. "block/oak_log_top"
+// a texture with a <name>.json next to it in the same pack is an animation strip, its frames take up consecutive indices
+pub fn load_textures(resource_packs: &ResourcePackStack, diagnostics: &mut AssetDiagnostics) -> anyhow::Result<(TextureMap, Vec<image::RgbaImage>, TextureAnimations)> {
+    let mut texture_map = TextureMap::new();
+    let mut images: Vec<image::RgbaImage> = vec![];
+    let mut animations = TextureAnimations::new();
+    for texture_file in resource_packs.files("textures", "png") {
+        let texture_name = texture_file.asset_name("textures");
+        let texture_path = &texture_file.path;
+        let image = match resource_packs.read(&texture_file).and_then(|bytes| Ok(image::load_from_memory(&bytes)?)) {
+            Ok(image) => image.to_rgba8(),
+            Err(err) => { diagnostics.error(texture_path, None, err); continue; }
+        };
+
+        let animation_file = resource_packs.sibling_file(&texture_file, &format!("{}.json", texture_file.name.trim_end_matches(".png")));
+        let (frames, animation) = if let Some(animation_file) = &animation_file {
+            let animation_path = &animation_file.path;
+            let animation: TextureAnimationDeserialize = match resource_packs.read_json(animation_file) {
+                Ok(animation) => animation,
+                Err(err) => { diagnostics.error(animation_path, None, err); continue; }
+            };
+
+            let (width, height) = image.dimensions();
+            if height % width != 0 {
+                diagnostics.error(texture_path, None, format!("animated texture is {}x{}, its height has to be a multiple of its width", width, height));
+                continue;
+            }
+            let frames = (0..height / width)
+                .map(|frame| image::imageops::crop_imm(&image, 0, frame * width, width, width).to_image())
+                .collect::<Vec<image::RgbaImage>>();
+            (frames, Some(animation))
+        } else {
+            (vec![image], None)
+        };
+
+        let expected_dimensions = images.first().unwrap_or(&frames[0]).dimensions();
+        if frames[0].dimensions() != expected_dimensions {
+            diagnostics.error(texture_path, None, format!("texture is {:?}, expected {:?} like the other textures", frames[0].dimensions(), expected_dimensions));
+            continue;
+        }
+
+        let texture_index = images.len();
+        if texture_index + frames.len() >= u16::MAX as usize {
+            diagnostics.error(texture_path, None, format!("too many textures, the limit is {}", u16::MAX - 1));
+            continue;
+        }
+
+        if let Some(animation) = animation {
+            let animation_path = &animation_file.as_ref().unwrap().path;
+            if animation.frame_time <= 0.0 {
+                diagnostics.error(animation_path, Some("frame_time".to_string()), format!("{} isn't positive", animation.frame_time));
+                continue;
+            }
+            let frame_order = animation.frames.unwrap_or_else(|| (0..frames.len() as u16).collect());
+            if frame_order.is_empty() {
+                diagnostics.error(animation_path, Some("frames".to_string()), "there are no frames");
+                continue;
+            }
+            if let Some((i, frame)) = frame_order.iter().enumerate().find(|(_, frame)| **frame as usize >= frames.len()) {
+                diagnostics.error(animation_path, Some(format!("frames[{}]", i)), format!("frame {} doesn't exist, the texture has {} frames", frame, frames.len()));
+                continue;
+            }
+
+            animations.push(TextureAnimation {
+                texture_index: texture_index as u16,
+                frame_layers: frame_order.into_iter().map(|frame| texture_index as u16 + frame).collect(),
+                frame_time: animation.frame_time,
+                interpolate: animation.interpolate,
+            });
+        }
+
+        texture_map.insert(texture_name, texture_index as u16);
+        images.extend(frames);
+    }
+
+    if images.is_empty() { anyhow::bail!("no textures found in the resource packs"); }
+
+    let (width, height) = images[0].dimensions();
+    texture_map.insert(MISSING_TEXTURE_NAME.to_string(), images.len() as u16);
+    texture_map.missing_texture_index = images.len() as u16;
+    images.push(missing_texture_image(width, height));
+
+    println!("Loaded textures: {}", texture_map.texture_names().cloned().collect::<Vec<String>>().join(", "));
+    if animations.len() > 0 { println!("Loaded texture animations: {}", animations.len()); }
+    Ok((texture_map, images, animations))
+}
+
+pub fn load_models(resource_packs: &ResourcePackStack, texture_map: &TextureMap, diagnostics: &mut AssetDiagnostics) -> anyhow::Result<BaseCuboidBlockModels> {
     let mut deserialized_cuboid_models = DeserializedCuboidModels::new();
 
     // load all base models
-    for entry_res in dir {
-        let Ok(entry) = entry_res else { continue; };
-        let model_name = entry.file_name().into_string().unwrap().trim_end_matches(".json").to_string();
-        let mut contents = String::new();
-        std::fs::File::open(entry.path())?.read_to_string(&mut contents)?;
-        let block_model: CuboidBlockModelDeserialize = serde_json::from_str(&contents)?;
-        deserialized_cuboid_models.insert(model_name, block_model);
-    }
-    let base_cuboid_models = deserialized_cuboid_models.to_base_cuboid_models();
+    for model_file in resource_packs.files("models", "json") {
+        match resource_packs.read_json::<CuboidBlockModelDeserialize>(&model_file) {
+            Ok(block_model) => deserialized_cuboid_models.insert(model_file.asset_name("models"), model_file.path, block_model),
+            Err(err) => diagnostics.error(&model_file.path, None, err),
+        }
+    }
+    let base_cuboid_models = deserialized_cuboid_models.to_base_cuboid_models(texture_map, diagnostics);
     println!("Loaded models: {}", base_cuboid_models.model_names().cloned().collect::<Vec<String>>().join(", "));
     Ok(base_cuboid_models)
 }
 
-pub fn load_blocks<T: Into<std::path::PathBuf>>(path: T, base_cuboid_block_models: &BaseCuboidBlockModels) -> anyhow::Result<(BlockMap, BlockList, BlockModelVariants, Vec<QuadRaw>)> {
-    // load directory
-    let path: std::path::PathBuf = path.into();
-    let dir = path.read_dir()?;
-
+// block ids are given out in the order the resource packs add the blocks,
+// when the assets are reloaded the blocks keep the ids from the previous block list so the loaded chunks stay valid
+pub fn load_blocks(resource_packs: &ResourcePackStack, base_cuboid_block_models: &BaseCuboidBlockModels, previous_block_list: Option<&BlockList>, diagnostics: &mut AssetDiagnostics) -> anyhow::Result<(BlockMap, BlockList, BlockModelVariants, Vec<QuadRaw>)> {
     let mut id: BlockId = 0;
     let mut quads: Vec<QuadRaw> = vec![];
     let mut block_models = BlockModelVariants::new();
     let mut block_map = BlockMap::new();
     let mut block_list = BlockList::new();
 
-    for entry_res in dir {
-        let Ok(entry) = entry_res else { continue; };
-
-        let block_name = entry.file_name().into_string().unwrap().trim_end_matches(".json").to_string();
+    let mut block_files = resource_packs.files("blocks", "json").into_iter()
+        .map(|block_file| (block_file.asset_name("blocks"), block_file))
+        .collect::<Vec<(String, AssetFile)>>();
+    if let Some(previous_block_list) = previous_block_list {
+        let mut ordered_block_files = vec![];
+        for previous_block in previous_block_list.iter() {
+            match block_files.iter().position(|(block_name, _)| block_name == previous_block.name()) {
+                Some(position) => ordered_block_files.push(block_files.remove(position)),
+                None => diagnostics.error(format!("blocks/{}.json", previous_block.name()), None, "the block was removed, blocks can't be removed while they're loaded"),
+            }
+        }
+        ordered_block_files.extend(block_files);
+        block_files = ordered_block_files;
+    }
 
+    for (block_name, block_file) in block_files {
+        let block_path = &block_file.path;
         // deserialize block model variants
-        let mut contents = String::new();
-        std::fs::File::open(entry.path())?.read_to_string(&mut contents)?;
-        let block_deserialize: BlockDeserialize = serde_json::from_str(&contents)?;
-
-        let block_info = BlockInformation::new(id, &block_name, block_deserialize.default_state, block_deserialize.base_properties.into());
-
-        let mut block_model_variants = vec![];
-        for variant in block_deserialize.variants {
-            let mut quad_block_model = base_cuboid_block_models.get(&variant.model).unwrap().bake();
-            quad_block_model.rotate(variant.rotation);
-
-            let mut quad_indices_per_face: [Box<[u16]>; FACE_DIRECTIONS_NUM] = [Box::new([]), Box::new([]), Box::new([]), Box::new([]), Box::new([]), Box::new([])];
-            for (i, model_quads ) in quad_block_model.quads_per_face.into_iter().enumerate() {
-                let quads_len = quads.len();
-                quads.extend(model_quads.iter().map(|f| f.into_raw()));
-                let quad_indices = (quads_len..quads.len()).map(|f| f as u16).collect::<Box<[u16]>>();
-                quad_indices_per_face[i] = quad_indices;
+        let block_deserialize: BlockDeserialize = match resource_packs.read_json(&block_file) {
+            Ok(block_deserialize) => block_deserialize,
+            Err(err) => { diagnostics.error(block_path, None, err); continue; }
+        };
+
+        // a block with an error isn't loaded, but the rest of its file is still checked so every problem is reported at once
+        let mut has_errors = false;
+        let base_properties = match Properties::try_from(block_deserialize.base_properties) {
+            Ok(base_properties) => base_properties,
+            Err(err) => {
+                diagnostics.error(block_path, Some("base_properties".to_string()), err);
+                has_errors = true;
+                Properties::default()
+            }
+        };
+
+        let mut default_state = block_deserialize.default_state;
+        let rotation_state = block_deserialize.rotation_state;
+        if let Some(rotation_state) = rotation_state {
+            let state_name = rotation_state.state_name();
+            match default_state.get(state_name) {
+                None => { default_state.insert(state_name.to_string(), Value::String(rotation_state.default_value().to_string())); },
+                Some(Value::String(value)) if rotation_state.rotations().iter().any(|(f, _)| f == value) => (),
+                Some(value) => {
+                    diagnostics.error(block_path, Some(format!("default_state.{}", state_name)), format!("{:?} isn't a value of the rotation state", value));
+                    has_errors = true;
+                }
             }
+        }
 
-            assert!(quads.len() < u16::MAX as usize);
+        let block_info = BlockInformation::new(id, &block_name, default_state, base_properties, rotation_state, block_deserialize.placement_states);
 
-            let quad_index_block_model = QuadIndexBlockModel {
-                quad_indices_per_face,
-                texture_indices_per_face: quad_block_model.texture_indices_per_face,
-                quad_culling_per_face: quad_block_model.quad_culling_per_face,
-            };
+        // the rotations the variants get for each value of the rotation state
+        let state_rotations = match rotation_state {
+            Some(rotation_state) => rotation_state.rotations().iter()
+                .map(|(value, rotation)| (Some((rotation_state.state_name(), *value)), *rotation))
+                .collect::<Vec<_>>(),
+            None => vec![(None, Vector3::zero())],
+        };
 
-            let block_model_variant = BlockModelVariant {
-                parent_model: variant.model,
-                quad_indices_per_face: quad_index_block_model.quad_indices_per_face,
-                texture_indices_per_face: quad_index_block_model.texture_indices_per_face,
-                quad_culling_per_face: quad_index_block_model.quad_culling_per_face,
-                required_state: variant.required_state,
-                compound: variant.compound,
-                rotation: variant.rotation,
-                pivot: variant.pivot,
-                hitboxes: variant.hitboxes,
-                properties: variant.properties
+        let mut block_model_variants = vec![];
+        for (variant_index, variant) in block_deserialize.variants.into_vec().into_iter().enumerate() {
+            let Some(base_model) = base_cuboid_block_models.get(&variant.model) else {
+                diagnostics.error(block_path, Some(format!("variants[{}].model", variant_index)), format!("model \"{}\" doesn't exist", variant.model));
+                continue;
             };
-            block_model_variants.push(block_model_variant);
+            // a variant needs every state it requires, so one the block doesn't have means it's never used
+            for (state_name, _) in variant.required_state.iter().filter(|(state_name, _)| block_info.default_state().get(state_name).is_none()) {
+                diagnostics.warning(block_path, Some(format!("variants[{}].required_state", variant_index)), format!("the block has no state \"{}\", the variant is never used", state_name));
+            }
+
+            for (state, state_rotation) in state_rotations.iter() {
+                let mut quad_block_model = base_model.bake();
+                quad_block_model.rotate(variant.rotation, variant.pivot, variant.uv_lock);
+                quad_block_model.rotate(*state_rotation, BLOCK_CENTER, variant.uv_lock);
+
+                // quads are referenced by u16 indices
+                let variant_quads_num = quad_block_model.quads_per_face.iter().map(|f| f.len()).sum::<usize>();
+                if quads.len() + variant_quads_num >= u16::MAX as usize {
+                    diagnostics.error(block_path, Some(format!("variants[{}]", variant_index)), format!("the quad buffer overflows, {} quads exceed the limit of {}", quads.len() + variant_quads_num, u16::MAX - 1));
+                    break;
+                }
+
+                let mut quad_indices_per_face: [Box<[u16]>; FACE_DIRECTIONS_NUM] = [Box::new([]), Box::new([]), Box::new([]), Box::new([]), Box::new([]), Box::new([])];
+                for (i, model_quads ) in quad_block_model.quads_per_face.into_iter().enumerate() {
+                    let quads_len = quads.len();
+                    quads.extend(model_quads.iter().map(|f| f.into_raw()));
+                    let quad_indices = (quads_len..quads.len()).map(|f| f as u16).collect::<Box<[u16]>>();
+                    quad_indices_per_face[i] = quad_indices;
+                }
+
+                let quad_index_block_model = QuadIndexBlockModel {
+                    quad_indices_per_face,
+                    texture_indices_per_face: quad_block_model.texture_indices_per_face,
+                    quad_culling_per_face: quad_block_model.quad_culling_per_face,
+                };
+
+                let mut required_state = variant.required_state.clone();
+                if let Some((state_name, value)) = state {
+                    required_state.push((state_name.to_string(), Value::String(value.to_string())));
+                }
+
+                let hitboxes = variant.hitboxes.iter()
+                    .map(|hitbox| hitbox.rotated(variant.rotation, variant.pivot).rotated(*state_rotation, BLOCK_CENTER))
+                    .collect();
+
+                let block_model_variant = BlockModelVariant {
+                    parent_model: variant.model.clone(),
+                    quad_indices_per_face: quad_index_block_model.quad_indices_per_face,
+                    texture_indices_per_face: quad_index_block_model.texture_indices_per_face,
+                    quad_culling_per_face: quad_index_block_model.quad_culling_per_face,
+                    required_state,
+                    compound: variant.compound,
+                    rotation: variant.rotation,
+                    pivot: variant.pivot,
+                    uv_lock: variant.uv_lock,
+                    hitboxes,
+                    properties: variant.properties
+                };
+                block_model_variants.push(block_model_variant);
+            }
         }
+        if has_errors { continue; }
         block_models.insert(block_name.clone(), block_model_variants.into_boxed_slice());
         block_map.insert(block_name, block_info.clone());
         block_list.push(block_info);
 
         match id.checked_add(1) {
             Some(new_id) => { id = new_id },
-            None => panic!("block id overflow")
+            None => {
+                diagnostics.error(block_path, None, format!("too many blocks, the limit is {}", BlockId::MAX as usize + 1));
+                break;
+            }
         }
     }
+
+    if block_map.get("air").is_none() {
+        diagnostics.error("blocks/air.json", None, "the \"air\" block is missing");
+    }
+
     Ok((block_map, block_list, block_models, quads))
 }
 
+pub struct Assets {
+    pub texture_map: TextureMap,
+    pub texture_images: Vec<image::RgbaImage>,
+    pub texture_animations: TextureAnimations,
+    pub base_models: BaseCuboidBlockModels,
+    pub block_map: BlockMap,
+    pub block_list: BlockList,
+    pub block_model_variants: BlockModelVariants,
+    pub quads: Vec<QuadRaw>,
+}
+
+// loads everything from the resource packs, the blocks keep the ids they had in the previous assets
+pub fn load_assets(resource_packs: &ResourcePackStack, previous_assets: Option<&Assets>, diagnostics: &mut AssetDiagnostics) -> anyhow::Result<Assets> {
+    let (texture_map, texture_images, texture_animations) = load_textures(resource_packs, diagnostics)?;
+    let base_models = load_models(resource_packs, &texture_map, diagnostics)?;
+    let (block_map, block_list, block_model_variants, quads) = load_blocks(resource_packs, &base_models, previous_assets.map(|f| &f.block_list), diagnostics)?;
+
+    Ok(Assets { texture_map, texture_images, texture_animations, base_models, block_map, block_list, block_model_variants, quads })
+}
+
 pub struct QuadIndicesMap(HashMap<BlockModelVariantDescriptorRaw, QuadIndexBlockModel>);
     
 impl QuadIndicesMap {
@@ -119,6 +310,38 @@ impl QuadIndicesMap {
     }
 }
 
+#[derive(Debug, Clone)]
+pub struct TextureMap {
+    textures: HashMap<String, u16>,
+    missing_texture_index: u16,
+}
+
+impl TextureMap {
+    pub fn new() -> Self {
+        Self { textures: HashMap::new(), missing_texture_index: 0 }
+    }
+
+    pub fn insert(&mut self, texture_name: String, texture_index: u16) {
+        self.textures.insert(texture_name, texture_index);
+    }
+
+    pub fn get(&self, texture_name: &str) -> Option<u16> {
+        self.textures.get(texture_name).copied()
+    }
+
+    pub fn missing_texture_index(&self) -> u16 {
+        self.missing_texture_index
+    }
+
+    pub fn len(&self) -> usize {
+        self.textures.len()
+    }
+
+    pub fn texture_names(&self) -> std::collections::hash_map::Keys<'_, String, u16> {
+        self.textures.keys()
+    }
+}
+
 #[derive(Debug, Clone)]
 pub struct BlockMap(HashMap<String, BlockInformation>);
 
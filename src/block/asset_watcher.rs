@@ -0,0 +1,23 @@
+use std::path::{Path, PathBuf};
+
+use crate::directory_watcher::DirectoryWatcher;
+
+use super::resource_pack::BASE_RESOURCE_PACK_PATH;
+
+// Watches the resource packs for changes, the game reloads them when a change settles
+pub struct AssetWatcher {
+    watcher: DirectoryWatcher,
+}
+
+impl AssetWatcher {
+    pub fn new(resource_pack_paths: &[PathBuf]) -> anyhow::Result<Self> {
+        let mut directories = vec![PathBuf::from(BASE_RESOURCE_PACK_PATH)];
+        directories.extend_from_slice(resource_pack_paths);
+        Ok(Self { watcher: DirectoryWatcher::new("asset", &directories, |_: &Path| true)? })
+    }
+
+    // true once after the watched files changed and then stayed untouched for a moment
+    pub fn poll(&mut self) -> bool {
+        !self.watcher.poll().is_empty()
+    }
+}
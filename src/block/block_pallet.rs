@@ -1,4 +1,4 @@
-use crate::{world::chunk::chunk_part::CHUNK_SIZE, BLOCK_MAP};
+use crate::{world::chunk::chunk_part::CHUNK_SIZE, assets};
 
 use super::Block;
 
@@ -19,7 +19,7 @@ impl BlockPallet {
     pub fn new_air() -> Self {
         let items = vec![Some(
             BlockPalletItem {
-                block: BLOCK_MAP.get("air").unwrap().clone().into(),
+                block: assets().block_map.get("air").unwrap().clone().into(),
                 count: (CHUNK_SIZE * CHUNK_SIZE * CHUNK_SIZE + 1) as BlockPalletItemId,
             },
         )];
@@ -120,6 +120,12 @@ impl BlockPallet {
         self.iter_mut().find(|p| p.1.block == *block)
     }
 
+    pub fn refresh_properties(&mut self) {
+        for item in self.values_mut() {
+            item.block.refresh_properties();
+        }
+    }
+
     #[inline]
     pub fn max_key(&self) -> Option<BlockPalletItemId> {
         self.ids().last()
@@ -11,6 +11,14 @@ impl BlockState {
     pub fn get(&self, state_name: &str) -> Option<&Value> {
         self.0.get(state_name)
     }
+
+    pub fn insert(&mut self, state_name: String, value: Value) -> Option<Value> {
+        self.0.insert(state_name, value)
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = (&String, &Value)> {
+        self.0.iter()
+    }
 }
 
 
@@ -25,3 +33,42 @@ pub enum Value {
     // Bytes(Box<[u8]>),
     Bool(bool)
 }
+
+impl Value {
+    // "true" and "false" are bools and numbers are numbers, everything else is a string
+    pub fn parse(value: &str) -> Self {
+        match value {
+            "true" => Self::Bool(true),
+            "false" => Self::Bool(false),
+            _ => match value.parse::<serde_json::Number>() {
+                Ok(number) => Self::Number(number),
+                Err(_) => Self::String(value.to_string()),
+            },
+        }
+    }
+}
+
+
+// "facing=north, half=top" with the states sorted by name
+impl std::fmt::Display for BlockState {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let mut states = self.0.iter().collect::<Vec<(&String, &Value)>>();
+        states.sort_by(|a, b| a.0.cmp(b.0));
+        for (i, (name, value)) in states.into_iter().enumerate() {
+            if i > 0 { write!(f, ", ")?; }
+            write!(f, "{}={}", name, value)?;
+        }
+        Ok(())
+    }
+}
+
+impl std::fmt::Display for Value {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Number(number) => write!(f, "{}", number),
+            Self::String(string) => write!(f, "{}", string),
+            Self::Struct(fields) => write!(f, "{{{}}}", fields.iter().map(|(name, value)| format!("{}={}", name, value)).collect::<Vec<String>>().join(", ")),
+            Self::Bool(bool) => write!(f, "{}", bool),
+        }
+    }
+}
\ No newline at end of file
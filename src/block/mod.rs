@@ -1,20 +1,29 @@
 use std::sync::Arc;
 
 use block_state::BlockState;
-use cgmath::Vector3;
+use cgmath::{InnerSpace, Vector3};
 use light::LIGHT_LEVEL_MAX_VALUE;
+use placement::{BlockPlacementContext, PlacementValue};
+use rotation::RotationState;
 use serde::Deserialize;
 
-use crate::{AIR_ID, BLOCK_LIST, BLOCK_MODEL_VARIANTS};
+use crate::{AIR_ID, assets};
 
 pub mod model;
 pub mod light;
 pub mod block_pallet;
 pub mod block_state;
 pub mod asset_loader;
+pub mod asset_diagnostics;
+pub mod resource_pack;
+pub mod asset_watcher;
 pub mod quad_buffer;
+pub mod texture_animation;
+pub mod rotation;
+pub mod placement;
 
 pub const FACE_DIRECTIONS_NUM: usize = std::mem::variant_count::<FaceDirection>();
+pub const ALPHA_MODES_NUM: usize = std::mem::variant_count::<AlphaMode>();
 pub type BlockId = u16;
 
 #[derive(Debug, Clone, PartialEq, serde::Deserialize, serde::Serialize)]
@@ -33,15 +42,33 @@ impl Block {
             block_state,
             properties: Properties::default(),
         };
+        block.refresh_properties();
 
-        let mut properties = BLOCK_LIST.get(block.id).unwrap().base_properties;
-        for variant in BLOCK_MODEL_VARIANTS.get_model_variants(&block).unwrap() {
+        block
+    }
+
+    // recomputes the properties from the current assets, they can change when the resource packs are reloaded
+    pub fn refresh_properties(&mut self) {
+        let assets = assets();
+        let mut properties = assets.block_list.get(self.id).unwrap().base_properties;
+        for variant in assets.block_model_variants.get_model_variants(self).unwrap() {
             properties.join_optional(variant.properties);
         }
 
-        block.properties = properties;
+        self.properties = properties;
+    }
 
-        block
+    // sets the states the block definition computes when the block is placed, the placement states override the rotation state
+    pub fn apply_placement(&mut self, context: &BlockPlacementContext) {
+        let assets = assets();
+        let Some(block_info) = assets.block_list.get(self.id) else { return; };
+        if let Some(rotation_state) = block_info.rotation_state() {
+            self.block_state.insert(rotation_state.state_name().to_string(), rotation_state.placement_value().value(context));
+        }
+        for (state_name, placement_value) in block_info.placement_states().iter() {
+            self.block_state.insert(state_name.clone(), placement_value.value(context));
+        }
+        self.refresh_properties();
     }
 
     #[inline]
@@ -71,15 +98,19 @@ pub struct BlockInformation {
     name: Arc<str>,
     default_state: BlockState,
     base_properties: Properties,
+    rotation_state: Option<RotationState>,
+    placement_states: Box<[(String, PlacementValue)]>,
 }
 
 impl BlockInformation {
-    pub fn new(id: BlockId, name: &str, default_state: BlockState, base_properties: Properties) -> Self {
+    pub fn new(id: BlockId, name: &str, default_state: BlockState, base_properties: Properties, rotation_state: Option<RotationState>, placement_states: Box<[(String, PlacementValue)]>) -> Self {
         Self {
             id,
             name: name.into(),
             default_state,
             base_properties,
+            rotation_state,
+            placement_states,
         }
     }
 
@@ -98,6 +129,14 @@ impl BlockInformation {
     pub fn base_properties(&self) -> &Properties {
         &self.base_properties
     }
+
+    pub fn rotation_state(&self) -> Option<RotationState> {
+        self.rotation_state
+    }
+
+    pub fn placement_states(&self) -> &[(String, PlacementValue)] {
+        &self.placement_states
+    }
 }
 
 impl Into<Block> for BlockInformation {
@@ -108,13 +147,7 @@ impl Into<Block> for BlockInformation {
             block_state: self.default_state.clone(),
             properties: Properties::default(),
         };
-
-        let mut properties = BLOCK_LIST.get(block.id).unwrap().base_properties;
-        for variant in BLOCK_MODEL_VARIANTS.get_model_variants(&block).unwrap() {
-            properties.join_optional(variant.properties);
-        }
-
-        block.properties = properties;
+        block.refresh_properties();
 
         block
     }
@@ -174,25 +207,52 @@ pub struct LightAttenuationDeserialize {
     ny: u8,
 }
 
-impl Into<LightAttenuation> for LightAttenuationDeserialize {
-    fn into(self) -> LightAttenuation {
-        assert!(self.px <= LIGHT_LEVEL_MAX_VALUE);
-        assert!(self.nx <= LIGHT_LEVEL_MAX_VALUE);
-        assert!(self.pz <= LIGHT_LEVEL_MAX_VALUE);
-        assert!(self.nz <= LIGHT_LEVEL_MAX_VALUE);
-        assert!(self.py <= LIGHT_LEVEL_MAX_VALUE);
-        assert!(self.ny <= LIGHT_LEVEL_MAX_VALUE);
-        
-        LightAttenuation([self.px | (self.nx << 4), self.pz | (self.nz << 4), self.py | (self.ny << 4)])
+fn opaque_light_attenuation() -> LightAttenuationDeserialize {
+    LightAttenuationDeserialize { px: LIGHT_LEVEL_MAX_VALUE, nx: LIGHT_LEVEL_MAX_VALUE, py: LIGHT_LEVEL_MAX_VALUE, ny: LIGHT_LEVEL_MAX_VALUE, pz: LIGHT_LEVEL_MAX_VALUE, nz: LIGHT_LEVEL_MAX_VALUE }
+}
+
+fn validate_light_level(field: &str, level: u8) -> Result<u8, String> {
+    if level > LIGHT_LEVEL_MAX_VALUE {
+        return Err(format!("{} is {}, the maximum light level is {}", field, level, LIGHT_LEVEL_MAX_VALUE));
+    }
+    Ok(level)
+}
+
+impl TryFrom<LightAttenuationDeserialize> for LightAttenuation {
+    type Error = String;
+    fn try_from(value: LightAttenuationDeserialize) -> Result<Self, Self::Error> {
+        let px = validate_light_level("light_attenuation.+x", value.px)?;
+        let nx = validate_light_level("light_attenuation.-x", value.nx)?;
+        let pz = validate_light_level("light_attenuation.+z", value.pz)?;
+        let nz = validate_light_level("light_attenuation.-z", value.nz)?;
+        let py = validate_light_level("light_attenuation.+y", value.py)?;
+        let ny = validate_light_level("light_attenuation.-y", value.ny)?;
+
+        Ok(LightAttenuation([px | (nx << 4), pz | (nz << 4), py | (ny << 4)]))
     }
 }
 
 pub fn deserialize_light_attenuation<'de, D>(deserialize: D) -> Result<LightAttenuation, D::Error> where D: serde::Deserializer<'de> {
-    LightAttenuationDeserialize::deserialize(deserialize).map(|f| f.into())
+    LightAttenuationDeserialize::deserialize(deserialize)?.try_into().map_err(serde::de::Error::custom)
 }
 
 pub fn deserialize_light_attenuation_option<'de, D>(deserialize: D) -> Result<Option<LightAttenuation>, D::Error> where D: serde::Deserializer<'de> {
-    Option::<LightAttenuationDeserialize>::deserialize(deserialize).map(|f| f.map(|f| f.into()))
+    Option::<LightAttenuationDeserialize>::deserialize(deserialize)?.map(|f| f.try_into()).transpose().map_err(serde::de::Error::custom)
+}
+
+fn validate_hardness(hardness: f32) -> Result<f32, String> {
+    if !hardness.is_finite() || hardness < 0.0 {
+        return Err(format!("hardness is {}, it has to be a positive number of seconds or 0", hardness));
+    }
+    Ok(hardness)
+}
+
+pub fn deserialize_hardness_option<'de, D>(deserialize: D) -> Result<Option<f32>, D::Error> where D: serde::Deserializer<'de> {
+    Option::<f32>::deserialize(deserialize)?.map(validate_hardness).transpose().map_err(serde::de::Error::custom)
+}
+
+pub fn deserialize_emitted_light_option<'de, D>(deserialize: D) -> Result<Option<u8>, D::Error> where D: serde::Deserializer<'de> {
+    Option::<u8>::deserialize(deserialize)?.map(|f| validate_light_level("emitted_light", f)).transpose().map_err(serde::de::Error::custom)
 }
 
 #[derive(serde::Deserialize, serde::Serialize, PartialEq, Clone, Copy, Debug)]
@@ -209,10 +269,22 @@ pub struct Properties {
     #[serde(default = "bool_true")]
     pub collideable: bool,
 
+    // e.g. water, nothing can stand on it
+    #[serde(default = "bool_false")]
+    pub fluid: bool,
+
     pub light_attenuation: LightAttenuation,
 
     #[serde(default = "u8_0")]
     pub emitted_light: u8,
+
+    // seconds it takes to break the block, 0 breaks it instantly
+    #[serde(default = "f32_1")]
+    pub hardness: f32,
+
+    // the color of the block when it's seen from above on a map
+    #[serde(default = "gray_map_color")]
+    pub map_color: [u8; 3],
 }
 
 #[derive(serde::Deserialize)]
@@ -229,10 +301,19 @@ pub struct PropertiesDeserialize {
     #[serde(default = "bool_true")]
     pub collideable: bool,
 
+    #[serde(default = "bool_false")]
+    pub fluid: bool,
+
     pub light_attenuation: LightAttenuationDeserialize,
 
     #[serde(default = "u8_0")]
     pub emitted_light: u8,
+
+    #[serde(default = "f32_1")]
+    pub hardness: f32,
+
+    #[serde(default = "gray_map_color")]
+    pub map_color: [u8; 3],
 }
 
 impl Default for PropertiesDeserialize {
@@ -242,22 +323,29 @@ impl Default for PropertiesDeserialize {
             targetable: true,
             replaceable: false,
             collideable: true,
-            light_attenuation: LightAttenuationDeserialize { px: LIGHT_LEVEL_MAX_VALUE, nx: LIGHT_LEVEL_MAX_VALUE, py: LIGHT_LEVEL_MAX_VALUE, ny: LIGHT_LEVEL_MAX_VALUE, pz: LIGHT_LEVEL_MAX_VALUE, nz: LIGHT_LEVEL_MAX_VALUE },
-            emitted_light: 0
+            fluid: false,
+            light_attenuation: opaque_light_attenuation(),
+            emitted_light: 0,
+            hardness: 1.0,
+            map_color: gray_map_color(),
         }       
     }
 }
 
-impl Into<Properties> for PropertiesDeserialize {
-    fn into(self) -> Properties {
-        Properties {
-            alpha_mode: self.alpha_mode,
-            targetable: self.targetable,
-            replaceable: self.replaceable,
-            collideable: self.collideable,
-            light_attenuation: self.light_attenuation.into(),
-            emitted_light: self.emitted_light,
-        }
+impl TryFrom<PropertiesDeserialize> for Properties {
+    type Error = String;
+    fn try_from(value: PropertiesDeserialize) -> Result<Self, Self::Error> {
+        Ok(Properties {
+            alpha_mode: value.alpha_mode,
+            targetable: value.targetable,
+            replaceable: value.replaceable,
+            collideable: value.collideable,
+            fluid: value.fluid,
+            light_attenuation: value.light_attenuation.try_into()?,
+            emitted_light: validate_light_level("emitted_light", value.emitted_light)?,
+            hardness: validate_hardness(value.hardness)?,
+            map_color: value.map_color,
+        })
     }
 }
 
@@ -267,8 +355,17 @@ impl Properties {
         self.targetable = optional.targetable.unwrap_or(self.targetable);
         self.replaceable = optional.replaceable.unwrap_or(self.replaceable);
         self.collideable = optional.collideable.unwrap_or(self.collideable);
+        self.fluid = optional.fluid.unwrap_or(self.fluid);
         self.light_attenuation = optional.light_attenuation.unwrap_or(self.light_attenuation);
         self.emitted_light = optional.emitted_light.unwrap_or(self.emitted_light);
+        self.hardness = optional.hardness.unwrap_or(self.hardness);
+        self.map_color = optional.map_color.unwrap_or(self.map_color);
+    }
+
+    // whether light passes through and comes out of blocks with either properties the same way
+    #[inline]
+    pub fn lights_like(&self, other: &Self) -> bool {
+        self.light_attenuation == other.light_attenuation && self.emitted_light == other.emitted_light
     }
 }
 
@@ -276,6 +373,8 @@ const fn bool_true() -> bool { true }
 const fn bool_false() -> bool { false }
 const fn none<T>() -> Option<T> { None }
 const fn u8_0() -> u8 { 0 }
+const fn f32_1() -> f32 { 1.0 }
+const fn gray_map_color() -> [u8; 3] { [128, 128, 128] }
 
 impl Default for Properties {
     fn default() -> Self {
@@ -284,8 +383,11 @@ impl Default for Properties {
             targetable: true,
             replaceable: false,
             collideable: true,
+            fluid: false,
             light_attenuation: LightAttenuation::opaque(),
             emitted_light: 0,
+            hardness: 1.0,
+            map_color: gray_map_color(),
         }
     }
 }
@@ -304,12 +406,23 @@ pub struct PropertiesOptional {
     #[serde(default = "none")]
     pub collideable: Option<bool>,
 
+    #[serde(default = "none")]
+    pub fluid: Option<bool>,
+
     #[serde(default = "none")]
     #[serde(deserialize_with = "deserialize_light_attenuation_option")]
     pub light_attenuation: Option<LightAttenuation>,
 
     #[serde(default = "none")]
+    #[serde(deserialize_with = "deserialize_emitted_light_option")]
     pub emitted_light: Option<u8>,
+
+    #[serde(default = "none")]
+    #[serde(deserialize_with = "deserialize_hardness_option")]
+    pub hardness: Option<f32>,
+
+    #[serde(default = "none")]
+    pub map_color: Option<[u8; 3]>,
 }
 
 impl Default for PropertiesOptional {
@@ -319,8 +432,11 @@ impl Default for PropertiesOptional {
             targetable: None,
             replaceable: None,
             collideable: None,
+            fluid: None,
             emitted_light: None,
             light_attenuation: None,
+            hardness: None,
+            map_color: None,
         }
     }
 }
@@ -340,6 +456,9 @@ impl Default for AlphaMode {
 }
 
 impl AlphaMode {
+    // in the order they are rendered
+    pub const ALL: [AlphaMode; ALPHA_MODES_NUM] = [Self::Opaque, Self::Transparent, Self::Translucent];
+
     pub fn is_opaque(&self) -> bool {
         *self == AlphaMode::Opaque
     }
@@ -357,6 +476,26 @@ pub enum FaceDirection {
 }
 
 impl FaceDirection {
+    pub const ALL: [FaceDirection; FACE_DIRECTIONS_NUM] = [
+        Self::PositiveX,
+        Self::NegativeX,
+        Self::PositiveZ,
+        Self::NegativeZ,
+        Self::PositiveY,
+        Self::NegativeY,
+    ];
+
+    pub const fn opposite(&self) -> Self {
+        match self {
+            Self::PositiveX => Self::NegativeX,
+            Self::NegativeX => Self::PositiveX,
+            Self::PositiveZ => Self::NegativeZ,
+            Self::NegativeZ => Self::PositiveZ,
+            Self::PositiveY => Self::NegativeY,
+            Self::NegativeY => Self::PositiveY,
+        }
+    }
+
     pub const fn normal_f32(&self) -> Vector3<f32> {
         match self {
             Self::PositiveX => Vector3 { x: 1.0, y: 0.0, z: 0.0 },
@@ -368,6 +507,15 @@ impl FaceDirection {
         }
     }
 
+    // the direction an axis aligned normal points at
+    pub fn from_normal(normal: Vector3<f32>) -> Option<Self> {
+        Self::ALL.into_iter().find(|face_direction| (face_direction.normal_f32() - normal).magnitude2() < 1e-4)
+    }
+
+    pub fn nearest(normal: Vector3<f32>) -> Self {
+        Self::ALL.into_iter().max_by(|a, b| a.normal_f32().dot(normal).total_cmp(&b.normal_f32().dot(normal))).unwrap()
+    }
+
     pub const fn normal_i32(&self) -> Vector3<i32> {
         match self {
             Self::PositiveX => Vector3 { x: 1, y: 0, z: 0 },
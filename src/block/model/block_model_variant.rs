@@ -2,7 +2,7 @@ use std::collections::HashMap;
 
 use cgmath::{Vector3, Zero};
 
-use crate::{block::{block_state::Value, Block, PropertiesOptional, FACE_DIRECTIONS_NUM}, collision::bounding_box::LocalBoundingBox, utils::bool_false};
+use crate::{block::{block_state::{BlockState, Value}, rotation::block_center, Block, PropertiesOptional, FACE_DIRECTIONS_NUM}, collision::bounding_box::LocalBoundingBox, utils::bool_false};
 
 use super::quad_block_model::QuadIndexBlockModelRef;
 
@@ -16,10 +16,19 @@ pub struct BlockModelVariant {
     pub compound: bool,
     pub rotation: Vector3<f32>,
     pub pivot: Vector3<f32>,
+    pub uv_lock: bool,
     pub hitboxes: Box<[LocalBoundingBox]>,
     pub properties: PropertiesOptional,
 }
 
+impl BlockModelVariant {
+    // a variant is used when the block has every state it requires, not just one of them,
+    // so e.g. a variant for facing=north and half=top isn't used for facing=north and half=bottom
+    pub fn matches(&self, block_state: &BlockState) -> bool {
+        self.required_state.iter().all(|(name, value)| block_state.get(name) == Some(value))
+    }
+}
+
 #[derive(serde::Deserialize)]
 pub struct BlockModelVariantDeserialize {
     pub model: String,
@@ -27,8 +36,12 @@ pub struct BlockModelVariantDeserialize {
     pub required_state: Vec<(String, Value)>,
     #[serde(default = "Vector3::zero")]
     pub rotation: Vector3<f32>,
-    #[serde(default = "Vector3::zero")]
+    // the point the model is rotated around, the block's center by default
+    #[serde(default = "block_center")]
     pub pivot: Vector3<f32>,
+    // keeps the textures facing the way they would on an unrotated block
+    #[serde(default = "bool_false")]
+    pub uv_lock: bool,
     #[serde(default)]
     pub hitboxes: Box<[LocalBoundingBox]>,
     #[serde(default = "bool_false")]
@@ -50,38 +63,20 @@ impl BlockModelVariants {
     #[inline]
     pub fn get_quad_block_models<'a>(&'a self, block: &Block) -> Option<Box<[QuadIndexBlockModelRef<'a>]>> {
         let mut models = vec![];
-        let Some(variants) = self.models.get(block.name.as_ref().into()) else { return None; };
+        let Some(variants) = self.models.get::<str>(block.name.as_ref()) else { return None; };
 
-        for variant in variants.iter() {
-            'inner: for (name, value) in variant.required_state.iter() {
-                let Some(block_state_value) = block.block_state.get(&name) else { continue 'inner; };
-                if *block_state_value == *value {
-                    let quad_index_block_model = QuadIndexBlockModelRef {
-                        quad_indices_per_face: &variant.quad_indices_per_face,
-                        texture_indices_per_face: &variant.texture_indices_per_face,
-                        quad_culling_per_face: &variant.quad_culling_per_face,
-                    };
-                    if !variant.compound {
-                        models.clear();
-                        models.push(quad_index_block_model);
-                        return Some(models.into_boxed_slice());
-                    }
-                    models.push(quad_index_block_model);
-                }
-            }
-            if variant.required_state.is_empty() {
-                let quad_index_block_model = QuadIndexBlockModelRef {
-                    quad_indices_per_face: &variant.quad_indices_per_face,
-                    texture_indices_per_face: &variant.texture_indices_per_face,
-                    quad_culling_per_face: &variant.quad_culling_per_face,
-                };
-                if !variant.compound {
-                    models.clear();
-                    models.push(quad_index_block_model);
-                    return Some(models.into_boxed_slice());
-                }
+        for variant in variants.iter().filter(|variant| variant.matches(&block.block_state)) {
+            let quad_index_block_model = QuadIndexBlockModelRef {
+                quad_indices_per_face: &variant.quad_indices_per_face,
+                texture_indices_per_face: &variant.texture_indices_per_face,
+                quad_culling_per_face: &variant.quad_culling_per_face,
+            };
+            if !variant.compound {
+                models.clear();
                 models.push(quad_index_block_model);
+                return Some(models.into_boxed_slice());
             }
+            models.push(quad_index_block_model);
         }
         Some(models.into_boxed_slice())
     }
@@ -90,16 +85,8 @@ impl BlockModelVariants {
     pub fn get_model_variants<'a>(&'a self, block: &Block) -> Option<Box<[&'a BlockModelVariant]>> {
         let variants = self.models.get(block.name.as_ref())?;
         let mut current_block_variants = vec![];
-        for variant in variants.iter() {
-            'inner: for (name, value) in variant.required_state.iter() {
-                let Some(block_state_value) = block.block_state.get(name) else { continue 'inner; };
-                if *block_state_value == *value && !variant.compound {
-                    current_block_variants.clear();
-                    current_block_variants.push(variant);
-                    return Some(current_block_variants.into_boxed_slice());
-                }
-            }
-            if variant.required_state.is_empty() && !variant.compound {
+        for variant in variants.iter().filter(|variant| variant.matches(&block.block_state)) {
+            if !variant.compound {
                 current_block_variants.clear();
                 current_block_variants.push(variant);
                 return Some(current_block_variants.into_boxed_slice());
@@ -1,9 +1,9 @@
-use std::collections::HashMap;
+use std::{collections::HashMap, path::PathBuf};
 
 use cgmath::{Vector2, Vector3};
 use serde::Deserialize;
 
-use crate::{block::{asset_loader::BaseCuboidBlockModels, FaceDirection, FACE_DIRECTIONS_NUM}, utils::{bool_true, none}};
+use crate::{block::{asset_diagnostics::AssetDiagnostics, asset_loader::{BaseCuboidBlockModels, TextureMap, MISSING_TEXTURE_NAME}, FaceDirection, FACE_DIRECTIONS_NUM}, utils::{bool_true, none}};
 
 use super::{quad_block_model::QuadBlockModel, ModelTexture, Quad};
 
@@ -12,7 +12,7 @@ pub struct CuboidBlockModelDeserialize {
     #[serde(default)]
     pub parent_model: Option<String>,
     #[serde(default)]
-    pub texture_overrides: HashMap<ModelTexture, ModelTexture>,
+    pub texture_overrides: HashMap<String, ModelTexture>,
     #[serde(default)]
     pub cuboids: Vec<CuboidDeserialize>,
 }
@@ -21,29 +21,47 @@ impl CuboidBlockModelDeserialize {
     pub fn override_textures(&mut self) {
         for cuboid in self.cuboids.iter_mut() {
             for face in cuboid.faces.iter_mut() {
-                if let Some(face) = face {
-                    if let Some(texture_override) = self.texture_overrides.get(&face.texture) {
-                        face.texture = texture_override.clone();
-                    }
+                let Some(face) = face else { continue; };
+                let ModelTexture::Placeholder(placeholder) = &face.texture else { continue; };
+                if let Some(texture_override) = self.texture_overrides.get(placeholder) {
+                    face.texture = texture_override.clone();
                 }
             }
         }
         self.texture_overrides.clear();
     }
     
+    // texture paths that aren't in the texture map with the names of the faces they're on,
+    // placeholders are left out since base models like "cube" keep theirs
+    pub fn missing_textures(&self, texture_map: &TextureMap) -> Vec<(String, Vec<&'static str>)> {
+        let mut missing_textures: Vec<(String, Vec<&'static str>)> = vec![];
+        for cuboid in self.cuboids.iter() {
+            for (face_name, face) in FACE_NAMES.iter().zip(cuboid.faces.iter()) {
+                let Some(face) = face else { continue; };
+                let ModelTexture::Texture(texture_name) = &face.texture else { continue; };
+                if texture_map.get(texture_name).is_some() { continue; }
+                match missing_textures.iter_mut().find(|(missing_texture, _)| missing_texture == texture_name) {
+                    Some((_, face_names)) if !face_names.contains(face_name) => face_names.push(face_name),
+                    Some(_) => (),
+                    None => missing_textures.push((texture_name.clone(), vec![face_name])),
+                }
+            }
+        }
+        missing_textures
+    }
+
     pub fn combine(&mut self, other: &Self) {
         self.cuboids.extend(other.cuboids.clone());
     }
 }
 
-impl TryInto<CuboidBlockModel> for CuboidBlockModelDeserialize {
-    type Error = ();
-    fn try_into(mut self) -> Result<CuboidBlockModel, Self::Error> {
+impl CuboidBlockModelDeserialize {
+    pub fn into_cuboid_block_model(mut self, texture_map: &TextureMap) -> Result<CuboidBlockModel, ()> {
         if self.parent_model.is_some() { return Err(()); }
         self.override_textures();
         let mut cuboids = vec![];
         for cuboid in self.cuboids {
-            cuboids.push(cuboid.try_into()?);
+            cuboids.push(cuboid.into_cuboid(texture_map));
         }
         Ok(CuboidBlockModel { cuboids })
     }
@@ -51,19 +69,37 @@ impl TryInto<CuboidBlockModel> for CuboidBlockModelDeserialize {
 
 #[derive(Debug)]
 pub struct DeserializedCuboidModels {
-    inner: HashMap<String, CuboidBlockModelDeserialize>
+    inner: HashMap<String, CuboidBlockModelDeserialize>,
+    paths: HashMap<String, PathBuf>,
 }
 
 impl DeserializedCuboidModels {
     pub fn new() -> Self {
-        Self { inner: HashMap::new() }
+        Self { inner: HashMap::new(), paths: HashMap::new() }
     }
 
-    pub fn insert(&mut self, name: String, model: CuboidBlockModelDeserialize) {
+    pub fn insert(&mut self, name: String, path: PathBuf, model: CuboidBlockModelDeserialize) {
+        self.paths.insert(name.clone(), path);
         self.inner.insert(name, model);
     }
 
-    pub fn to_base_cuboid_models(mut self) -> BaseCuboidBlockModels {
+    // follows the parents of a model that couldn't be resolved, returns the chain if it leads back to the model
+    fn parent_cycle(&self, model_name: &str) -> Option<Vec<String>> {
+        let mut chain = vec![model_name.to_string()];
+        let mut current = model_name;
+        while let Some(parent_model) = self.inner.get(current).and_then(|model| model.parent_model.as_deref()) {
+            if parent_model == model_name {
+                chain.push(parent_model.to_string());
+                return Some(chain);
+            }
+            if chain.iter().any(|f| f == parent_model) { return None; }
+            chain.push(parent_model.to_string());
+            current = parent_model;
+        }
+        None
+    }
+
+    pub fn to_base_cuboid_models(mut self, texture_map: &TextureMap, diagnostics: &mut AssetDiagnostics) -> BaseCuboidBlockModels {
         let mut base_cuboid_models = BaseCuboidBlockModels::new();
         let mut parentless_cuboid_models: HashMap<String, CuboidBlockModelDeserialize> = HashMap::new();
 
@@ -94,8 +130,28 @@ impl DeserializedCuboidModels {
             }
         }
 
+        // whatever is left has a parent that doesn't exist or is part of a cycle
+        let mut unresolved_model_names = self.inner.keys().cloned().collect::<Vec<String>>();
+        unresolved_model_names.sort();
+        for name in unresolved_model_names.iter() {
+            let parent_model = self.inner[name].parent_model.as_ref().unwrap();
+            let message = if !self.paths.contains_key(parent_model) {
+                format!("model \"{}\" doesn't exist", parent_model)
+            } else if let Some(cycle) = self.parent_cycle(name) {
+                format!("models form a cycle: {}", cycle.join(" -> "))
+            } else {
+                format!("model \"{}\" couldn't be loaded", parent_model)
+            };
+            diagnostics.error(&self.paths[name], Some("parent_model".to_string()), message);
+        }
+
         for (name, model) in parentless_cuboid_models {
-            if let Ok(model) = model.try_into() {
+            for (texture_name, face_names) in model.missing_textures(texture_map) {
+                diagnostics.warning(&self.paths[&name], Some("texture".to_string()),
+                    format!("model \"{}\" is missing the texture \"{}\" on its {} face{}, using the \"{}\" texture instead",
+                        name, texture_name, face_names.join(", "), if face_names.len() > 1 { "s" } else { "" }, MISSING_TEXTURE_NAME));
+            }
+            if let Ok(model) = model.into_cuboid_block_model(texture_map) {
                 base_cuboid_models.insert(name, model);
             }
         }
@@ -143,23 +199,13 @@ pub struct CuboidDeserialize {
     pub faces: [Option<CuboidFaceDeserialize>; FACE_DIRECTIONS_NUM]
 }
 
-impl TryInto<Cuboid> for CuboidDeserialize {
-    type Error = ();
-    fn try_into(self) -> Result<Cuboid, Self::Error> {
-        let mut faces = std::array::from_fn(|_| None);
-        for (i, face) in self.faces.into_iter().enumerate() {
-            if let Some(face) = face {
-                faces[i] = Some(face.try_into()?);
-            } else {
-                faces[i] = None;
-            }
-        }
-
-        Ok(Cuboid {
+impl CuboidDeserialize {
+    pub fn into_cuboid(self, texture_map: &TextureMap) -> Cuboid {
+        Cuboid {
             start: self.start,
             end: self.end,
-            faces
-        })
+            faces: self.faces.map(|face| face.map(|face| face.into_cuboid_face(texture_map)))
+        }
     }
 }
 
@@ -262,16 +308,19 @@ pub struct CuboidFaceDeserialize {
     pub culling: bool    
 }
 
-impl TryInto<CuboidFace> for CuboidFaceDeserialize {
-    type Error = ();
-    fn try_into(self) -> Result<CuboidFace, Self::Error> {
-        let ModelTexture::Index(texture_index) = self.texture else { return Err(()); };
-        Ok(CuboidFace {
+impl CuboidFaceDeserialize {
+    // unresolved placeholders and unknown textures fall back to the missing texture
+    pub fn into_cuboid_face(self, texture_map: &TextureMap) -> CuboidFace {
+        let texture_index = match &self.texture {
+            ModelTexture::Texture(texture_name) => texture_map.get(texture_name).unwrap_or(texture_map.missing_texture_index()),
+            ModelTexture::Placeholder(_) => texture_map.missing_texture_index(),
+        };
+        CuboidFace {
             uv_start: self.uv_start,
             uv_end: self.uv_end,
             texture_index,
             culling: self.culling,
-        })
+        }
     }
 }
 
@@ -302,6 +351,9 @@ pub struct CuboidFacesDeserialize {
     ny: Option<CuboidFaceDeserialize>,
 }
 
+// the faces' names in the model files in the order of FaceDirection
+const FACE_NAMES: [&str; FACE_DIRECTIONS_NUM] = ["+x", "-x", "+z", "-z", "+y", "-y"];
+
 impl Into<[Option<CuboidFaceDeserialize>; FACE_DIRECTIONS_NUM]> for CuboidFacesDeserialize {
     fn into(self) -> [Option<CuboidFaceDeserialize>; FACE_DIRECTIONS_NUM] {
         [self.px, self.nx, self.pz, self.nz, self.py, self.ny]
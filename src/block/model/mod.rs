@@ -3,9 +3,9 @@ pub mod block_model_variant;
 pub mod quad_block_model;
 
 use block_model_variant::BlockModelVariantDeserialize;
-use cgmath::{Deg, Rotation, Vector2, Vector3, Zero};
+use cgmath::{InnerSpace, Vector2, Vector3};
 
-use super::{block_state::{BlockState, Value}, light::{LightLevel, LIGHT_LEVEL_BITS}, quad_buffer::QuadBuffer, AlphaMode, Block, FaceDirection, Properties, PropertiesDeserialize, PropertiesOptional, FACE_DIRECTIONS_NUM};
+use super::{block_state::{BlockState, Value}, rotation::RotationState, placement::PlacementValue, light::{LightLevel, LIGHT_LEVEL_BITS}, quad_buffer::QuadBuffer, AlphaMode, Block, FaceDirection, Properties, PropertiesDeserialize, PropertiesOptional, FACE_DIRECTIONS_NUM};
 
 
 #[derive(serde::Deserialize)]
@@ -15,13 +15,30 @@ pub struct BlockDeserialize {
     pub default_state: BlockState,
     #[serde(default)]
     pub base_properties: PropertiesDeserialize,
+    // every variant is also rotated for each value of the state, see RotationState
+    #[serde(default)]
+    pub rotation_state: Option<RotationState>,
+    // states computed from how the block is placed, see PlacementValue
+    #[serde(default)]
+    pub placement_states: Box<[(String, PlacementValue)]>,
 }
 
+// "#name" is a placeholder filled in by the texture overrides of the child models,
+// anything else is a texture path relative to the textures directory e.g. "block/oak_log_top"
 #[derive(Clone, PartialEq, Eq, Debug, Hash, serde::Deserialize)]
-#[serde(untagged)]
+#[serde(from = "String")]
 pub enum ModelTexture {
     Placeholder(String),
-    Index(u16)
+    Texture(String),
+}
+
+impl From<String> for ModelTexture {
+    fn from(value: String) -> Self {
+        match value.strip_prefix('#') {
+            Some(placeholder) => Self::Placeholder(placeholder.to_string()),
+            None => Self::Texture(value),
+        }
+    }
 }
 
 #[derive(Debug, Clone)]
@@ -31,7 +48,47 @@ pub struct Quad {
     pub uv: [Vector2<f32>; 4],
 }
 
+// the directions the u and v of a face's texture run along, they match the vertex order of Cuboid::append_quads
+fn face_uv_axes(face_direction: FaceDirection) -> (Vector3<f32>, Vector3<f32>) {
+    match face_direction {
+        FaceDirection::PositiveX => (-Vector3::unit_z(), Vector3::unit_y()),
+        FaceDirection::NegativeX => (Vector3::unit_z(), Vector3::unit_y()),
+        FaceDirection::PositiveZ => (Vector3::unit_x(), Vector3::unit_y()),
+        FaceDirection::NegativeZ => (-Vector3::unit_x(), Vector3::unit_y()),
+        FaceDirection::PositiveY => (Vector3::unit_z(), Vector3::unit_x()),
+        FaceDirection::NegativeY => (-Vector3::unit_z(), Vector3::unit_x()),
+    }
+}
+
+// the uv at the start of the face's u and v axes and how much it changes along each of them
+pub type UvRect = (Vector2<f32>, Vector2<f32>, Vector2<f32>);
+
 impl Quad {
+    // where the vertices are along the face's u and v axes, from 0 to 1
+    fn face_coordinates(&self, face_direction: FaceDirection) -> [Vector2<f32>; 4] {
+        let (u_axis, v_axis) = face_uv_axes(face_direction);
+        let fraction = |axis: Vector3<f32>, position: Vector3<f32>| {
+            let min = self.vertex_positions.iter().map(|f| f.dot(axis)).fold(f32::INFINITY, f32::min);
+            let max = self.vertex_positions.iter().map(|f| f.dot(axis)).fold(f32::NEG_INFINITY, f32::max);
+            if max - min <= f32::EPSILON { 0.0 } else { (position.dot(axis) - min) / (max - min) }
+        };
+        self.vertex_positions.map(|position| Vector2::new(fraction(u_axis, position), fraction(v_axis, position)))
+    }
+
+    pub fn uv_rect(&self, face_direction: FaceDirection) -> Option<UvRect> {
+        let face_coordinates = self.face_coordinates(face_direction);
+        let uv_at = |corner: Vector2<f32>| face_coordinates.iter().position(|f| (f - corner).magnitude2() < 1e-4).map(|i| self.uv[i]);
+        let start = uv_at(Vector2::new(0.0, 0.0))?;
+        let u_end = uv_at(Vector2::new(1.0, 0.0))?;
+        let v_end = uv_at(Vector2::new(0.0, 1.0))?;
+        Some((start, u_end - start, v_end - start))
+    }
+
+    // lays the uv rect over the quad the way an unrotated quad facing the direction would have it
+    pub fn apply_uv_rect(&mut self, face_direction: FaceDirection, (start, u, v): UvRect) {
+        self.uv = self.face_coordinates(face_direction).map(|f| start + u * f.x + v * f.y);
+    }
+
     pub fn into_raw(&self) -> QuadRaw {
         QuadRaw {
             vertex_positions: self.vertex_positions.map(|f| f.extend(0.0).into()),
@@ -80,3 +137,15 @@ impl Face {
 #[repr(C)]
 #[derive(Clone, Copy, bytemuck::Zeroable, bytemuck::Pod)]
 pub struct FacePacked(u128);
+
+impl FacePacked {
+    #[inline]
+    pub fn block_position(&self) -> [u8; 3] {
+        let position_bits = (self.0 >> (8 * LIGHT_LEVEL_BITS)) as u32;
+        [
+            (position_bits & 0b11111) as u8,
+            (position_bits >> 5 & 0b11111) as u8,
+            (position_bits >> (2 * 5) & 0b11111) as u8,
+        ]
+    }
+}
@@ -1,6 +1,6 @@
-use cgmath::{Deg, Rotation, Vector3};
+use cgmath::{Rotation, Vector3, Zero};
 
-use crate::block::FACE_DIRECTIONS_NUM;
+use crate::block::{rotation::{rotation_matrix, snap}, FaceDirection, FACE_DIRECTIONS_NUM};
 
 use super::Quad;
 
@@ -12,17 +12,46 @@ pub struct QuadBlockModel {
 }
 
 impl QuadBlockModel {
-    pub fn rotate(&mut self, rotation: Vector3<f32>) {
-        let euler_angles = cgmath::Euler::new(Deg(rotation.x), Deg(rotation.y), Deg(rotation.z));
-        let rotation_matrix = cgmath::Basis3::from(euler_angles);
-        for quads in self.quads_per_face.iter_mut() {
-            for quad in quads {
+    // rotates the model around the pivot, the quads move to the faces their normals end up pointing at so they're culled by the right neighbours,
+    // with uv_lock the textures keep the orientation of unrotated faces instead of turning with the model
+    pub fn rotate(&mut self, rotation: Vector3<f32>, pivot: Vector3<f32>, uv_lock: bool) {
+        if rotation == Vector3::zero() { return; }
+        let rotation_matrix = rotation_matrix(rotation);
+        let mut quads_per_face: [Vec<Quad>; FACE_DIRECTIONS_NUM] = std::array::from_fn(|_| vec![]);
+        let mut texture_indices_per_face: [Vec<u16>; FACE_DIRECTIONS_NUM] = std::array::from_fn(|_| vec![]);
+        let mut quad_culling_per_face: [Vec<bool>; FACE_DIRECTIONS_NUM] = std::array::from_fn(|_| vec![]);
+
+        for face_num in 0..FACE_DIRECTIONS_NUM {
+            let quads = self.quads_per_face[face_num].iter();
+            let texture_indices = self.texture_indices_per_face[face_num].iter();
+            let quad_culling = self.quad_culling_per_face[face_num].iter();
+            for ((quad, texture_index), culling) in quads.zip(texture_indices).zip(quad_culling) {
+                let mut quad = quad.clone();
+                let uv_rect = if uv_lock { FaceDirection::from_normal(quad.normal).and_then(|face_direction| quad.uv_rect(face_direction)) } else { None };
+
                 for position in quad.vertex_positions.iter_mut() {
-                    *position = rotation_matrix.rotate_vector(*position);
+                    *position = snap(rotation_matrix.rotate_vector(*position - pivot) + pivot);
+                }
+                quad.normal = snap(rotation_matrix.rotate_vector(quad.normal));
+
+                // faces that don't end up axis aligned can't be hidden by a neighbour
+                let (face_direction, culling) = match FaceDirection::from_normal(quad.normal) {
+                    Some(face_direction) => (face_direction, *culling),
+                    None => (FaceDirection::nearest(quad.normal), false),
+                };
+                if let Some(uv_rect) = uv_rect {
+                    quad.apply_uv_rect(face_direction, uv_rect);
                 }
-                quad.normal = rotation_matrix.rotate_vector(quad.normal);
+
+                quads_per_face[face_direction as usize].push(quad);
+                texture_indices_per_face[face_direction as usize].push(*texture_index);
+                quad_culling_per_face[face_direction as usize].push(culling);
             }
         }
+
+        self.quads_per_face = quads_per_face.map(|f| f.into_boxed_slice());
+        self.texture_indices_per_face = texture_indices_per_face.map(|f| f.into_boxed_slice());
+        self.quad_culling_per_face = quad_culling_per_face.map(|f| f.into_boxed_slice());
     }
 }
 
@@ -0,0 +1,51 @@
+use cgmath::Vector3;
+
+use super::{block_state::Value, rotation::{facing_name, horizontal_direction}, FaceDirection};
+
+// How the player placed a block, the block definitions turn it into the placed block's state
+#[derive(Debug, Clone, Copy)]
+pub struct BlockPlacementContext {
+    // the face of the clicked block the new block is placed against
+    pub clicked_face: FaceDirection,
+    // where the clicked block was hit, relative to the placed block's origin
+    pub hit_point: Vector3<f32>,
+    pub player_direction: Vector3<f32>,
+    pub sneaking: bool,
+}
+
+// A block state that's computed when the block is placed e.g. "placement_states": [["half", "hit_half"]]
+#[derive(serde::Deserialize, Clone, Copy, Debug, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum PlacementValue {
+    // "north", "south", "east", "west", "up" or "down", pointing away from the clicked block
+    ClickedFace,
+    // "x", "y" or "z", the axis the clicked face lies across
+    ClickedAxis,
+    // "north", "south", "east" or "west", towards the player
+    PlayerFacing,
+    // "top" or "bottom", the half of the block the clicked point is in
+    HitHalf,
+    // true when the player is sneaking
+    Sneaking,
+}
+
+impl PlacementValue {
+    pub fn value(&self, context: &BlockPlacementContext) -> Value {
+        let name = match self {
+            Self::ClickedFace => facing_name(context.clicked_face),
+            Self::ClickedAxis => match context.clicked_face {
+                FaceDirection::PositiveX | FaceDirection::NegativeX => "x",
+                FaceDirection::PositiveY | FaceDirection::NegativeY => "y",
+                FaceDirection::PositiveZ | FaceDirection::NegativeZ => "z",
+            },
+            Self::PlayerFacing => facing_name(horizontal_direction(context.player_direction).opposite()),
+            Self::HitHalf => match context.clicked_face {
+                FaceDirection::PositiveY => "bottom",
+                FaceDirection::NegativeY => "top",
+                _ => if context.hit_point.y > 0.5 { "top" } else { "bottom" },
+            },
+            Self::Sneaking => return Value::Bool(context.sneaking),
+        };
+        Value::String(name.to_string())
+    }
+}
@@ -0,0 +1,176 @@
+use std::{collections::BTreeMap, io::Read, path::{Path, PathBuf}};
+
+// the game's own assets, they're at the bottom of the game's stack
+pub const BASE_RESOURCE_PACK_PATH: &str = "./assets";
+
+enum ResourcePackFiles {
+    // file name -> path on disk
+    Directory(BTreeMap<String, PathBuf>),
+    // file name -> contents, zips are read into memory when they're opened
+    Zip(BTreeMap<String, Vec<u8>>),
+}
+
+// A directory or a zip file laid out like ./assets, file names are relative to its root e.g. "models/cube.json"
+pub struct ResourcePack {
+    path: PathBuf,
+    files: ResourcePackFiles,
+}
+
+impl ResourcePack {
+    pub fn open<T: Into<PathBuf>>(path: T) -> anyhow::Result<Self> {
+        let path: PathBuf = path.into();
+        let files = if path.is_dir() {
+            let mut files = BTreeMap::new();
+            Self::find_directory_files(&path, &path, &mut files)?;
+            ResourcePackFiles::Directory(files)
+        } else if path.extension().is_some_and(|extension| extension == "zip") {
+            let mut archive = zip::ZipArchive::new(std::fs::File::open(&path)?)?;
+            let mut files = BTreeMap::new();
+            for i in 0..archive.len() {
+                let mut file = archive.by_index(i)?;
+                if !file.is_file() { continue; }
+                let Some(name) = file.enclosed_name() else { continue; };
+                let name = Self::file_name(&name);
+                let mut contents = vec![];
+                file.read_to_end(&mut contents)?;
+                files.insert(name, contents);
+            }
+            ResourcePackFiles::Zip(files)
+        } else {
+            anyhow::bail!("resource pack {:?} isn't a directory or a zip file", path);
+        };
+
+        Ok(Self { path, files })
+    }
+
+    fn file_name(relative_path: &Path) -> String {
+        relative_path.components()
+            .map(|component| component.as_os_str().to_string_lossy())
+            .collect::<Vec<_>>()
+            .join("/")
+    }
+
+    fn find_directory_files(root: &Path, path: &Path, files: &mut BTreeMap<String, PathBuf>) -> anyhow::Result<()> {
+        for entry in path.read_dir()? {
+            let entry_path = entry?.path();
+            if entry_path.is_dir() {
+                Self::find_directory_files(root, &entry_path, files)?;
+            } else {
+                files.insert(Self::file_name(entry_path.strip_prefix(root)?), entry_path);
+            }
+        }
+        Ok(())
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    fn file_names(&self) -> Box<dyn Iterator<Item = &String> + '_> {
+        match &self.files {
+            ResourcePackFiles::Directory(files) => Box::new(files.keys()),
+            ResourcePackFiles::Zip(files) => Box::new(files.keys()),
+        }
+    }
+
+    fn contains(&self, name: &str) -> bool {
+        match &self.files {
+            ResourcePackFiles::Directory(files) => files.contains_key(name),
+            ResourcePackFiles::Zip(files) => files.contains_key(name),
+        }
+    }
+
+    fn read(&self, name: &str) -> anyhow::Result<Vec<u8>> {
+        match &self.files {
+            ResourcePackFiles::Directory(files) => {
+                let path = files.get(name).ok_or_else(|| anyhow::anyhow!("{} doesn't exist", name))?;
+                Ok(std::fs::read(path)?)
+            },
+            ResourcePackFiles::Zip(files) => files.get(name).cloned().ok_or_else(|| anyhow::anyhow!("{} doesn't exist", name)),
+        }
+    }
+}
+
+// A file picked from the stack, it comes from the last pack that contains it
+#[derive(Debug, Clone)]
+pub struct AssetFile {
+    // relative to the pack's root e.g. "models/cube.json"
+    pub name: String,
+    pub pack_index: usize,
+    // where the file is for error messages, zipped files are shown inside the zip's path
+    pub path: PathBuf,
+}
+
+impl AssetFile {
+    // the file's name relative to the directory without the extension e.g. "block/oak_log_top" in "textures"
+    pub fn asset_name(&self, directory: &str) -> String {
+        let name = self.name.strip_prefix(directory).unwrap_or(&self.name).trim_start_matches('/');
+        match name.rsplit_once('.') {
+            Some((name, _)) => name.to_string(),
+            None => name.to_string(),
+        }
+    }
+}
+
+// Resource packs in the order they're applied, files in later packs replace the ones with the same name in earlier packs
+pub struct ResourcePackStack {
+    packs: Vec<ResourcePack>,
+}
+
+impl ResourcePackStack {
+    // the base pack is at the bottom of the stack, the game uses BASE_RESOURCE_PACK_PATH
+    pub fn new<P: Into<PathBuf>>(base_pack_path: P, resource_pack_paths: &[PathBuf]) -> anyhow::Result<Self> {
+        let mut packs = vec![ResourcePack::open(base_pack_path)?];
+        for path in resource_pack_paths {
+            packs.push(ResourcePack::open(path)?);
+        }
+
+        let stack = Self { packs };
+        println!("Active resource packs: {}", stack.packs.iter().map(|pack| pack.path.display().to_string()).collect::<Vec<String>>().join(" < "));
+        Ok(stack)
+    }
+
+    pub fn packs(&self) -> &[ResourcePack] {
+        &self.packs
+    }
+
+    fn asset_file(&self, name: &str, pack_index: usize) -> AssetFile {
+        AssetFile { name: name.to_string(), pack_index, path: self.packs[pack_index].path.join(name) }
+    }
+
+    pub fn file(&self, name: &str) -> Option<AssetFile> {
+        let pack_index = self.packs.iter().rposition(|pack| pack.contains(name))?;
+        Some(self.asset_file(name, pack_index))
+    }
+
+    // the file with the name from the same pack as another file, e.g. the animation of a texture
+    pub fn sibling_file(&self, file: &AssetFile, name: &str) -> Option<AssetFile> {
+        self.packs[file.pack_index].contains(name).then(|| self.asset_file(name, file.pack_index))
+    }
+
+    // files in the directory and its subdirectories with the extension,
+    // ordered by the pack that first added them and then by name so that packs only append to the order
+    pub fn files(&self, directory: &str, extension: &str) -> Vec<AssetFile> {
+        let prefix = format!("{}/", directory);
+        let suffix = format!(".{}", extension);
+        let mut first_pack_indices: Vec<(usize, &String)> = vec![];
+        let mut seen_names = std::collections::HashSet::new();
+        for (pack_index, pack) in self.packs.iter().enumerate() {
+            for name in pack.file_names().filter(|name| name.starts_with(&prefix) && name.ends_with(&suffix)) {
+                if seen_names.insert(name) { first_pack_indices.push((pack_index, name)); }
+            }
+        }
+
+        first_pack_indices.into_iter()
+            .map(|(_, name)| self.file(name).unwrap())
+            .collect()
+    }
+
+    pub fn read(&self, file: &AssetFile) -> anyhow::Result<Vec<u8>> {
+        self.packs[file.pack_index].read(&file.name)
+    }
+
+    pub fn read_json<T: serde::de::DeserializeOwned>(&self, file: &AssetFile) -> anyhow::Result<T> {
+        Ok(serde_json::from_slice(&self.read(file)?)?)
+    }
+}
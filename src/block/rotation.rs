@@ -0,0 +1,99 @@
+use cgmath::{Deg, Vector3};
+
+use super::{placement::PlacementValue, FaceDirection};
+
+pub const BLOCK_CENTER: Vector3<f32> = Vector3 { x: 0.5, y: 0.5, z: 0.5 };
+
+pub fn block_center() -> Vector3<f32> {
+    BLOCK_CENTER
+}
+
+// rotation in degrees around the x, y and z axes
+pub fn rotation_matrix(rotation: Vector3<f32>) -> cgmath::Basis3<f32> {
+    cgmath::Basis3::from(cgmath::Euler::new(Deg(rotation.x), Deg(rotation.y), Deg(rotation.z)))
+}
+
+// rotated vertices are snapped so that 90 degree rotations land exactly on the block's grid
+pub fn snap(vector: Vector3<f32>) -> Vector3<f32> {
+    vector.map(|f| (f * 65536.0).round() / 65536.0)
+}
+
+// A block state that picks how the block's models are rotated, every variant gets a rotated copy for each of its values
+#[derive(serde::Deserialize, Clone, Copy, Debug, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum RotationState {
+    // "axis": "x", "y" or "z", the models stand along y
+    Axis,
+    // "facing": "north", "south", "east", "west", "up" or "down", the models face north
+    Facing,
+    // "facing": "north", "south", "east" or "west", the models face north
+    HorizontalFacing,
+}
+
+impl RotationState {
+    pub fn state_name(&self) -> &'static str {
+        match self {
+            Self::Axis => "axis",
+            Self::Facing | Self::HorizontalFacing => "facing",
+        }
+    }
+
+    pub fn default_value(&self) -> &'static str {
+        match self {
+            Self::Axis => "y",
+            Self::Facing | Self::HorizontalFacing => "north",
+        }
+    }
+
+    // the state's values and the rotations that turn the models to them
+    pub fn rotations(&self) -> &'static [(&'static str, Vector3<f32>)] {
+        const AXIS: &[(&str, Vector3<f32>)] = &[
+            ("y", Vector3 { x: 0.0, y: 0.0, z: 0.0 }),
+            ("x", Vector3 { x: 0.0, y: 0.0, z: 90.0 }),
+            ("z", Vector3 { x: 90.0, y: 0.0, z: 0.0 }),
+        ];
+        const FACING: &[(&str, Vector3<f32>)] = &[
+            ("north", Vector3 { x: 0.0, y: 0.0, z: 0.0 }),
+            ("south", Vector3 { x: 0.0, y: 180.0, z: 0.0 }),
+            ("east", Vector3 { x: 0.0, y: -90.0, z: 0.0 }),
+            ("west", Vector3 { x: 0.0, y: 90.0, z: 0.0 }),
+            ("up", Vector3 { x: 90.0, y: 0.0, z: 0.0 }),
+            ("down", Vector3 { x: -90.0, y: 0.0, z: 0.0 }),
+        ];
+        match self {
+            Self::Axis => AXIS,
+            Self::Facing => FACING,
+            Self::HorizontalFacing => &FACING[..4],
+        }
+    }
+
+    // how a placed block gets the state, logs follow the clicked face's axis, facing blocks point away from the clicked block
+    // and horizontally facing blocks turn towards the player
+    pub fn placement_value(&self) -> PlacementValue {
+        match self {
+            Self::Axis => PlacementValue::ClickedAxis,
+            Self::Facing => PlacementValue::ClickedFace,
+            Self::HorizontalFacing => PlacementValue::PlayerFacing,
+        }
+    }
+}
+
+pub fn facing_name(face_direction: FaceDirection) -> &'static str {
+    match face_direction {
+        FaceDirection::PositiveX => "east",
+        FaceDirection::NegativeX => "west",
+        FaceDirection::PositiveZ => "south",
+        FaceDirection::NegativeZ => "north",
+        FaceDirection::PositiveY => "up",
+        FaceDirection::NegativeY => "down",
+    }
+}
+
+// the horizontal direction the player is looking in
+pub fn horizontal_direction(player_direction: Vector3<f32>) -> FaceDirection {
+    if player_direction.x.abs() > player_direction.z.abs() {
+        if player_direction.x > 0.0 { FaceDirection::PositiveX } else { FaceDirection::NegativeX }
+    } else {
+        if player_direction.z > 0.0 { FaceDirection::PositiveZ } else { FaceDirection::NegativeZ }
+    }
+}
\ No newline at end of file
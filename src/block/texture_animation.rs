@@ -0,0 +1,134 @@
+use std::sync::Arc;
+
+use wgpu::util::DeviceExt;
+
+// metadata stored next to an animated texture as <texture name>.json,
+// the texture itself is a vertical strip of square frames
+#[derive(serde::Deserialize, Debug)]
+pub struct TextureAnimationDeserialize {
+    // order in which the frames of the strip are shown, defaults to top to bottom
+    #[serde(default)]
+    pub frames: Option<Vec<u16>>,
+    // seconds per frame
+    pub frame_time: f32,
+    #[serde(default)]
+    pub interpolate: bool,
+}
+
+#[derive(Debug, Clone)]
+pub struct TextureAnimation {
+    // index that the faces reference, it's the layer of the strip's first frame
+    pub texture_index: u16,
+    pub frame_layers: Box<[u16]>,
+    pub frame_time: f32,
+    pub interpolate: bool,
+}
+
+impl TextureAnimation {
+    pub fn frame(&self, time: f32) -> TextureFrameRaw {
+        let frame_progress = time / self.frame_time;
+        let frame = frame_progress as usize % self.frame_layers.len();
+        let next_frame = (frame + 1) % self.frame_layers.len();
+
+        TextureFrameRaw {
+            current: self.frame_layers[frame] as u32,
+            next: self.frame_layers[next_frame] as u32,
+            blend: if self.interpolate { frame_progress.fract() } else { 0.0 },
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct TextureAnimations(Vec<TextureAnimation>);
+
+impl TextureAnimations {
+    pub fn new() -> Self {
+        Self(vec![])
+    }
+
+    pub fn push(&mut self, animation: TextureAnimation) {
+        self.0.push(animation);
+    }
+
+    pub fn len(&self) -> usize {
+        self.0.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+
+    pub fn iter(&self) -> std::slice::Iter<'_, TextureAnimation> {
+        self.0.iter()
+    }
+}
+
+impl Default for TextureAnimations {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[repr(C)]
+#[derive(Clone, Copy, Debug, bytemuck::Pod, bytemuck::Zeroable)]
+pub struct TextureFrameRaw {
+    current: u32,
+    next: u32,
+    blend: f32,
+}
+
+// Maps every texture index to the layer that is currently shown,
+// so animated textures don't need the chunks to be remeshed
+#[derive(Clone)]
+pub struct TextureFrameBuffer {
+    frames: Vec<TextureFrameRaw>,
+    buffer: Arc<wgpu::Buffer>,
+    start: std::time::Instant,
+}
+
+impl TextureFrameBuffer {
+    pub fn new(device: &wgpu::Device, textures_num: usize) -> Self {
+        let frames = (0..textures_num as u32)
+            .map(|texture_index| TextureFrameRaw { current: texture_index, next: texture_index, blend: 0.0 })
+            .collect::<Vec<TextureFrameRaw>>();
+
+        let buffer = device.create_buffer_init(&wgpu::util::BufferInitDescriptor {
+            label: Some("TextureFrameBuffer"),
+            contents: bytemuck::cast_slice(&frames),
+            usage: wgpu::BufferUsages::STORAGE | wgpu::BufferUsages::COPY_DST
+        });
+
+        Self { frames, buffer: Arc::new(buffer), start: std::time::Instant::now() }
+    }
+
+    pub fn update(&mut self, animations: &TextureAnimations) {
+        let time = self.start.elapsed().as_secs_f32();
+        for animation in animations.iter() {
+            self.frames[animation.texture_index as usize] = animation.frame(time);
+        }
+    }
+
+    pub fn update_buffer(&self, queue: &wgpu::Queue) {
+        queue.write_buffer(&self.buffer, 0, bytemuck::cast_slice(&self.frames));
+    }
+
+    pub fn bind_group_layout_entry(&self, binding: u32) -> wgpu::BindGroupLayoutEntry {
+        wgpu::BindGroupLayoutEntry {
+            binding,
+            count: None,
+            ty: wgpu::BindingType::Buffer {
+                has_dynamic_offset: false,
+                min_binding_size: None,
+                ty: wgpu::BufferBindingType::Storage { read_only: true }
+            },
+            visibility: wgpu::ShaderStages::VERTEX,
+        }
+    }
+
+    pub fn bind_group_entry(&self, binding: u32) -> wgpu::BindGroupEntry<'_> {
+        wgpu::BindGroupEntry {
+            binding,
+            resource: self.buffer.as_entire_binding(),
+        }
+    }
+}
@@ -1,6 +1,6 @@
-use cgmath::Vector3;
+use cgmath::{Rotation, Vector3};
 
-use crate::{block::FaceDirection, global_vector::GlobalVecF, world::chunk::chunk_part::CHUNK_SIZE};
+use crate::{block::{rotation::{rotation_matrix, snap}, FaceDirection}, global_vector::GlobalVecF, world::chunk::chunk_part::CHUNK_SIZE};
 
 #[derive(serde::Deserialize, Debug, Clone, Copy)]
 pub struct LocalBoundingBox {
@@ -17,34 +17,57 @@ impl Default for LocalBoundingBox {
     }
 }
 
-pub struct GlobalBoundingBox {
-    pub start: GlobalVecF,
-    pub end: GlobalVecF,
-}
+impl LocalBoundingBox {
+    // the box that encloses this one after it's rotated around the pivot, exact for rotations by multiples of 90 degrees
+    pub fn rotated(&self, rotation: Vector3<f32>, pivot: Vector3<f32>) -> Self {
+        let rotation_matrix = rotation_matrix(rotation);
+        let corners = (0..8).map(|i| Vector3::new(
+            if i & 1 == 0 { self.start.x } else { self.end.x },
+            if i & 2 == 0 { self.start.y } else { self.end.y },
+            if i & 4 == 0 { self.start.z } else { self.end.z },
+        )).map(|corner| snap(rotation_matrix.rotate_vector(corner - pivot) + pivot));
+
+        let mut start = Vector3::new(f32::INFINITY, f32::INFINITY, f32::INFINITY);
+        let mut end = Vector3::new(f32::NEG_INFINITY, f32::NEG_INFINITY, f32::NEG_INFINITY);
+        for corner in corners {
+            start = start.zip(corner, f32::min);
+            end = end.zip(corner, f32::max);
+        }
+        Self { start, end }
+    }
 
-impl GlobalBoundingBox {
-    pub fn test_outline(&self) {
-        use lyon_tessellation::path::Path;
-        use lyon_tessellation::geom::point;
-        use lyon_tessellation::math::Point;
-        use lyon_tessellation::VertexBuffers;
-        let mut path_builder = Path::builder();
-        path_builder.begin(point(self.start.local().x, self.start.local().y));
-        
-        path_builder.end(false);
-        let path = path_builder.build();
-        let mut buffers: VertexBuffers<Point, u16> = VertexBuffers::new();
-        {
-            let mut vertex_builder = lyon_tessellation::geometry_builder::simple_builder(&mut buffers);
-            let mut tesselator = lyon_tessellation::StrokeTessellator::new();
-            let mut stroke_options = lyon_tessellation::StrokeOptions::default();
-            stroke_options = stroke_options.with_line_width(2.0);
-
-            tesselator.tessellate(&path, &stroke_options, &mut vertex_builder).unwrap();
+    #[inline]
+    pub fn offset(&self, offset: Vector3<f32>) -> Self {
+        Self { start: self.start + offset, end: self.end + offset }
+    }
+
+    #[inline]
+    pub fn intersects(&self, other: &Self) -> bool {
+        (0..3).all(|axis| self.start[axis] < other.end[axis] && self.end[axis] > other.start[axis])
+    }
+
+    // how far the box can move along the axis before it runs into the other box,
+    // boxes that don't overlap on the other axes or already intersect don't stop it
+    pub fn clip_movement(&self, other: &Self, axis: usize, movement: f32) -> f32 {
+        const EPSILON: f32 = 0.0001;
+        let overlaps = (0..3).filter(|f| *f != axis).all(|f| self.start[f] < other.end[f] - EPSILON && self.end[f] > other.start[f] + EPSILON);
+        if !overlaps { return movement; }
+
+        if movement > 0.0 && self.end[axis] <= other.start[axis] + EPSILON {
+            movement.min((other.start[axis] - self.end[axis]).max(0.0))
+        } else if movement < 0.0 && self.start[axis] >= other.end[axis] - EPSILON {
+            movement.max((other.end[axis] - self.start[axis]).min(0.0))
+        } else {
+            movement
         }
     }
 }
 
+pub struct GlobalBoundingBox {
+    pub start: GlobalVecF,
+    pub end: GlobalVecF,
+}
+
 pub struct Ray {
     pub origin: GlobalVecF,
     pub direction: Vector3<f32>,
@@ -0,0 +1,310 @@
+use std::collections::BTreeMap;
+
+use cgmath::Vector3;
+
+use crate::{block::{block_state::{BlockState, Value}, Block}, global_vector::{GlobalVecF, GlobalVecU}, world::{game_mode::GameMode, World}, assets};
+
+// What an argument accepts, it parses the typed word and knows the words it can be completed to
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ArgumentKind {
+    Int,
+    // a coordinate on the axis, "~" or "~-2.5" is relative to the player's position
+    Coordinate(usize),
+    // a coordinate that's rounded down to the block it's in
+    BlockCoordinate(usize),
+    // a block name with an optional state e.g. torch[facing=north]
+    Block,
+    GameMode,
+    // one of the words
+    Literal(&'static [&'static str]),
+    // a number or one of the names of a number
+    NamedInt(&'static [(&'static str, i64)]),
+    // the name of a command, the registry completes it
+    CommandName,
+    // any word, e.g. a file name
+    Word,
+}
+
+#[derive(Debug, Clone)]
+pub enum Argument {
+    Int(i64),
+    Float(f64),
+    Block(Block),
+    GameMode(GameMode),
+    Word(String),
+}
+
+impl ArgumentKind {
+    pub fn parse(&self, word: &str, world: &World) -> anyhow::Result<Argument> {
+        match self {
+            Self::Int => Ok(Argument::Int(word.parse().map_err(|_| anyhow::anyhow!("'{}' isn't a whole number", word))?)),
+            Self::Coordinate(axis) => Ok(Argument::Float(parse_coordinate(word, *axis, world)?)),
+            Self::BlockCoordinate(axis) => Ok(Argument::Float(parse_coordinate(word, *axis, world)?.floor())),
+            Self::Block => Ok(Argument::Block(parse_block(word)?)),
+            Self::GameMode => GameMode::ALL.into_iter()
+                .find(|game_mode| game_mode.name() == word)
+                .map(Argument::GameMode)
+                .ok_or_else(|| anyhow::anyhow!("unknown game mode '{}'", word)),
+            Self::Literal(words) => match words.contains(&word) {
+                true => Ok(Argument::Word(word.to_string())),
+                false => Err(anyhow::anyhow!("expected one of {}, got '{}'", words.join(", "), word)),
+            },
+            Self::NamedInt(names) => match names.iter().find(|(name, _)| *name == word) {
+                Some((_, number)) => Ok(Argument::Int(*number)),
+                None => Ok(Argument::Int(word.parse().map_err(|_| anyhow::anyhow!("'{}' isn't a number or one of {}", word, names.iter().map(|(name, _)| *name).collect::<Vec<&str>>().join(", ")))?)),
+            },
+            Self::CommandName | Self::Word => Ok(Argument::Word(word.to_string())),
+        }
+    }
+
+    // every word that completes the typed prefix
+    pub fn completions(&self, prefix: &str) -> Vec<String> {
+        let words: Vec<String> = match self {
+            Self::Int | Self::CommandName | Self::Word => vec![],
+            Self::Coordinate(_) | Self::BlockCoordinate(_) => vec!["~".to_string()],
+            Self::Block => {
+                let assets = assets();
+                match prefix.split_once('[') {
+                    // the states of the block, only the last one is completed
+                    Some((block_name, states)) => {
+                        let Some(block_info) = assets.block_map.get(block_name) else { return vec![]; };
+                        let typed_states = states.rsplit_once(',').map_or("", |(typed_states, _)| typed_states);
+                        let mut state_names = block_info.default_state().iter().map(|(name, _)| name.clone()).collect::<Vec<String>>();
+                        state_names.sort();
+                        state_names.into_iter()
+                            .map(|name| match typed_states.is_empty() {
+                                true => format!("{}[{}=", block_name, name),
+                                false => format!("{}[{},{}=", block_name, typed_states, name),
+                            })
+                            .collect()
+                    },
+                    None => {
+                        let mut block_names = assets.block_list.iter().map(|block_info| block_info.name().to_string()).collect::<Vec<String>>();
+                        block_names.sort();
+                        block_names
+                    },
+                }
+            },
+            Self::GameMode => GameMode::ALL.iter().map(|game_mode| game_mode.name().to_string()).collect(),
+            Self::Literal(words) => words.iter().map(|word| word.to_string()).collect(),
+            Self::NamedInt(names) => names.iter().map(|(name, _)| name.to_string()).collect(),
+        };
+        words.into_iter().filter(|word| word.starts_with(prefix)).collect()
+    }
+}
+
+// the coordinate has to be finite and in a block whose position fits in an i32
+fn parse_coordinate(word: &str, axis: usize, world: &World) -> anyhow::Result<f64> {
+    let parse = |word: &str| word.parse::<f64>().ok().filter(|f| f.is_finite()).ok_or_else(|| anyhow::anyhow!("'{}' isn't a coordinate", word));
+    let coordinate = match word.strip_prefix('~') {
+        Some(offset) => {
+            let player_position: Vector3<f64> = world.player.position.into();
+            let offset = if offset.is_empty() { 0.0 } else { parse(offset)? };
+            player_position[axis] + offset
+        },
+        None => parse(word)?,
+    };
+    if !(i32::MIN as f64..i32::MAX as f64 + 1.0).contains(&coordinate) {
+        anyhow::bail!("'{}' is outside of the world, the blocks go from {} to {}", word, i32::MIN, i32::MAX);
+    }
+    Ok(coordinate)
+}
+
+// "stone" or "torch[facing=north,lit=true]", states that aren't given are left at the block's default
+fn parse_block(word: &str) -> anyhow::Result<Block> {
+    let (block_name, states) = match word.split_once('[') {
+        Some((block_name, states)) => (block_name, Some(states.strip_suffix(']').ok_or_else(|| anyhow::anyhow!("the block's states aren't closed with ']'"))?)),
+        None => (word, None),
+    };
+    let assets = assets();
+    let block_info = assets.block_map.get(block_name).ok_or_else(|| anyhow::anyhow!("unknown block '{}'", block_name))?;
+    let mut block: Block = block_info.clone().into();
+    let Some(states) = states else { return Ok(block); };
+
+    let mut block_state: BlockState = block_info.default_state().clone();
+    for state in states.split(',').filter(|f| !f.is_empty()) {
+        let (name, value) = state.split_once('=').ok_or_else(|| anyhow::anyhow!("expected name=value, got '{}'", state))?;
+        if block_state.get(name).is_none() {
+            anyhow::bail!("{} has no state '{}'", block_name, name);
+        }
+        block_state.insert(name.to_string(), Value::parse(value));
+    }
+    block.block_state = block_state;
+    block.refresh_properties();
+    Ok(block)
+}
+
+#[derive(Debug, Clone, Copy)]
+pub struct ArgumentSpec {
+    pub name: &'static str,
+    pub kind: ArgumentKind,
+    pub optional: bool,
+}
+
+impl ArgumentSpec {
+    pub const fn required(name: &'static str, kind: ArgumentKind) -> Self {
+        Self { name, kind, optional: false }
+    }
+
+    pub const fn optional(name: &'static str, kind: ArgumentKind) -> Self {
+        Self { name, kind, optional: true }
+    }
+}
+
+// The parsed arguments in the order of the command's specs, optional arguments that weren't given are None
+pub struct Arguments(Vec<Option<Argument>>);
+
+impl Arguments {
+    #[inline]
+    pub fn get(&self, index: usize) -> Option<&Argument> {
+        self.0.get(index)?.as_ref()
+    }
+
+    pub fn int(&self, index: usize) -> Option<i64> {
+        match self.get(index)? {
+            Argument::Int(int) => Some(*int),
+            _ => None,
+        }
+    }
+
+    pub fn float(&self, index: usize) -> Option<f64> {
+        match self.get(index)? {
+            Argument::Float(float) => Some(*float),
+            Argument::Int(int) => Some(*int as f64),
+            _ => None,
+        }
+    }
+
+    pub fn block(&self, index: usize) -> Option<&Block> {
+        match self.get(index)? {
+            Argument::Block(block) => Some(block),
+            _ => None,
+        }
+    }
+
+    pub fn game_mode(&self, index: usize) -> Option<GameMode> {
+        match self.get(index)? {
+            Argument::GameMode(game_mode) => Some(*game_mode),
+            _ => None,
+        }
+    }
+
+    pub fn word(&self, index: usize) -> Option<&str> {
+        match self.get(index)? {
+            Argument::Word(word) => Some(word),
+            _ => None,
+        }
+    }
+
+    // the three coordinates starting at the index
+    pub fn position(&self, index: usize) -> Option<GlobalVecF> {
+        Some(GlobalVecF::from(Vector3::new(self.float(index)?, self.float(index + 1)?, self.float(index + 2)?)))
+    }
+
+    pub fn block_position(&self, index: usize) -> Option<GlobalVecU> {
+        Some(GlobalVecU::from(Vector3::new(self.float(index)? as i32, self.float(index + 1)? as i32, self.float(index + 2)? as i32)))
+    }
+}
+
+// What the commands ask of the game, it's done after they ran because they can't reach it
+#[derive(Debug, Default, Clone, Copy)]
+pub struct CommandRequests {
+    pub reload_resource_packs: bool,
+}
+
+// What the commands get to work with
+pub struct CommandContext<'a> {
+    pub world: &'a mut World,
+    pub commands: &'a CommandRegistry,
+    pub requests: &'a mut CommandRequests,
+}
+
+pub struct Command {
+    pub name: &'static str,
+    pub description: &'static str,
+    pub arguments: Vec<ArgumentSpec>,
+    // returns the text that's written to the console
+    pub run: fn(&mut CommandContext, &Arguments) -> anyhow::Result<String>,
+}
+
+impl Command {
+    // e.g. "setblock <x> <y> <z> <block>", optional arguments are in square brackets
+    pub fn usage(&self) -> String {
+        let mut usage = self.name.to_string();
+        for argument in self.arguments.iter() {
+            match argument.optional {
+                true => usage += &format!(" [{}]", argument.name),
+                false => usage += &format!(" <{}>", argument.name),
+            }
+        }
+        usage
+    }
+}
+
+// The commands by name, a line is run by the command named by its first word with the other words as its arguments
+pub struct CommandRegistry {
+    commands: BTreeMap<&'static str, Command>,
+}
+
+impl CommandRegistry {
+    pub fn new() -> Self {
+        Self { commands: BTreeMap::new() }
+    }
+
+    pub fn register(&mut self, command: Command) {
+        self.commands.insert(command.name, command);
+    }
+
+    #[inline]
+    pub fn get(&self, name: &str) -> Option<&Command> {
+        self.commands.get(name)
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = &Command> {
+        self.commands.values()
+    }
+
+    pub fn execute(&self, world: &mut World, requests: &mut CommandRequests, line: &str) -> anyhow::Result<String> {
+        let mut words = line.trim().trim_start_matches('/').split_whitespace();
+        let Some(name) = words.next() else { return Ok(String::new()); };
+        let command = self.get(name).ok_or_else(|| anyhow::anyhow!("unknown command '{}', 'help' lists the commands", name))?;
+        let words = words.collect::<Vec<&str>>();
+        if words.len() > command.arguments.len() {
+            anyhow::bail!("too many arguments, usage: {}", command.usage());
+        }
+
+        let mut arguments = vec![];
+        for (i, spec) in command.arguments.iter().enumerate() {
+            match words.get(i) {
+                Some(word) => arguments.push(Some(spec.kind.parse(word, world).map_err(|err| anyhow::anyhow!("{}: {}", spec.name, err))?)),
+                None if spec.optional => arguments.push(None),
+                None => anyhow::bail!("missing <{}>, usage: {}", spec.name, command.usage()),
+            }
+        }
+
+        (command.run)(&mut CommandContext { world, commands: self, requests }, &Arguments(arguments))
+    }
+
+    // the words the line's last word can be completed to
+    pub fn completions(&self, line: &str) -> Vec<String> {
+        let line = line.trim_start().trim_start_matches('/');
+        let mut words = line.split_whitespace().collect::<Vec<&str>>();
+        // the cursor is after a space, so a new word is started
+        if line.is_empty() || line.ends_with(char::is_whitespace) {
+            words.push("");
+        }
+        let Some((prefix, previous_words)) = words.split_last() else { return vec![]; };
+
+        let command_names = || self.commands.keys().filter(|name| name.starts_with(prefix)).map(|name| name.to_string()).collect();
+        match previous_words.split_first() {
+            None => command_names(),
+            Some((name, arguments)) => {
+                let Some(spec) = self.get(name).and_then(|command| command.arguments.get(arguments.len())) else { return vec![]; };
+                match spec.kind {
+                    ArgumentKind::CommandName => command_names(),
+                    kind => kind.completions(prefix),
+                }
+            },
+        }
+    }
+}
@@ -0,0 +1,386 @@
+use cgmath::{Vector2, Vector3};
+
+use std::path::{Component, Path, PathBuf};
+
+use crate::{global_vector::GlobalVecU, world::{chunk::chunk_generator::WORLD_SEED, structure::{schematic::STRUCTURE_EXTENSION, vox::VOX_EXTENSION, Structure, STRUCTURES_DIRECTORY}, world_time::{DAY_LENGTH, MIDNIGHT, NOON}}};
+
+use super::command::{ArgumentKind, ArgumentSpec, Arguments, Command, CommandContext, CommandRegistry};
+
+const MAX_RENDER_DISTANCE: i64 = 64;
+const MAX_RELIGHT_RADIUS: i64 = 8;
+
+const POSITION: [ArgumentSpec; 3] = [
+    ArgumentSpec::required("x", ArgumentKind::Coordinate(0)),
+    ArgumentSpec::required("y", ArgumentKind::Coordinate(1)),
+    ArgumentSpec::required("z", ArgumentKind::Coordinate(2)),
+];
+
+const OPTIONAL_BLOCK_POSITION: [ArgumentSpec; 3] = [
+    ArgumentSpec::optional("x", ArgumentKind::BlockCoordinate(0)),
+    ArgumentSpec::optional("y", ArgumentKind::BlockCoordinate(1)),
+    ArgumentSpec::optional("z", ArgumentKind::BlockCoordinate(2)),
+];
+
+const TIMES_OF_DAY: &[(&str, i64)] = &[("day", 1000), ("noon", NOON as i64), ("night", 13000), ("midnight", MIDNIGHT as i64)];
+
+pub fn register_commands(commands: &mut CommandRegistry) {
+    commands.register(Command {
+        name: "help",
+        description: "lists the commands or shows how to use one",
+        arguments: vec![ArgumentSpec::optional("command", ArgumentKind::CommandName)],
+        run: help,
+    });
+    commands.register(Command {
+        name: "tp",
+        description: "moves the player to the position",
+        arguments: POSITION.to_vec(),
+        run: tp,
+    });
+    commands.register(Command {
+        name: "setblock",
+        description: "sets the block at the position",
+        arguments: vec![
+            ArgumentSpec::required("x", ArgumentKind::BlockCoordinate(0)),
+            ArgumentSpec::required("y", ArgumentKind::BlockCoordinate(1)),
+            ArgumentSpec::required("z", ArgumentKind::BlockCoordinate(2)),
+            ArgumentSpec::required("block", ArgumentKind::Block),
+        ],
+        run: setblock,
+    });
+    commands.register(Command {
+        name: "pos1",
+        description: "sets the selection's first corner to the position or the targeted block",
+        arguments: OPTIONAL_BLOCK_POSITION.to_vec(),
+        run: pos1,
+    });
+    commands.register(Command {
+        name: "pos2",
+        description: "sets the selection's second corner to the position or the targeted block",
+        arguments: OPTIONAL_BLOCK_POSITION.to_vec(),
+        run: pos2,
+    });
+    commands.register(Command {
+        name: "fill",
+        description: "sets every block in the selection",
+        arguments: vec![ArgumentSpec::required("block", ArgumentKind::Block)],
+        run: fill,
+    });
+    commands.register(Command {
+        name: "replace",
+        description: "replaces the blocks in the selection with the name of the first block by the second block",
+        arguments: vec![
+            ArgumentSpec::required("from", ArgumentKind::Block),
+            ArgumentSpec::required("to", ArgumentKind::Block),
+        ],
+        run: replace,
+    });
+    commands.register(Command {
+        name: "hollow",
+        description: "sets the selection's faces to the block and its inside to air",
+        arguments: vec![ArgumentSpec::required("block", ArgumentKind::Block)],
+        run: hollow,
+    });
+    commands.register(Command {
+        name: "clone",
+        description: "copies the selection so its lowest corner is at the position",
+        arguments: vec![
+            ArgumentSpec::required("x", ArgumentKind::BlockCoordinate(0)),
+            ArgumentSpec::required("y", ArgumentKind::BlockCoordinate(1)),
+            ArgumentSpec::required("z", ArgumentKind::BlockCoordinate(2)),
+        ],
+        run: clone,
+    });
+    commands.register(Command {
+        name: "schematic",
+        description: "saves the selection as a structure or places a structure or sponge .schem file at the position or the targeted block",
+        arguments: vec![
+            ArgumentSpec::required("save|load", ArgumentKind::Literal(&["save", "load"])),
+            ArgumentSpec::required("name", ArgumentKind::Word),
+            ArgumentSpec::optional("x", ArgumentKind::BlockCoordinate(0)),
+            ArgumentSpec::optional("y", ArgumentKind::BlockCoordinate(1)),
+            ArgumentSpec::optional("z", ArgumentKind::BlockCoordinate(2)),
+        ],
+        run: schematic,
+    });
+    commands.register(Command {
+        name: "vox",
+        description: "exports the selection as a MagicaVoxel model or imports one at the position or the targeted block, <name>.json maps the palette to blocks",
+        arguments: vec![
+            ArgumentSpec::required("export|import", ArgumentKind::Literal(&["export", "import"])),
+            ArgumentSpec::required("name", ArgumentKind::Word),
+            ArgumentSpec::optional("x", ArgumentKind::BlockCoordinate(0)),
+            ArgumentSpec::optional("y", ArgumentKind::BlockCoordinate(1)),
+            ArgumentSpec::optional("z", ArgumentKind::BlockCoordinate(2)),
+        ],
+        run: vox,
+    });
+    commands.register(Command {
+        name: "undo",
+        description: "undoes the last edit",
+        arguments: vec![],
+        run: undo,
+    });
+    commands.register(Command {
+        name: "redo",
+        description: "redoes the last undone edit",
+        arguments: vec![],
+        run: redo,
+    });
+    commands.register(Command {
+        name: "relight",
+        description: "spreads the light through the chunks around the player again",
+        arguments: vec![ArgumentSpec::optional("radius", ArgumentKind::Int)],
+        run: relight,
+    });
+    commands.register(Command {
+        name: "save",
+        description: "saves the player and the loaded chunks",
+        arguments: vec![],
+        run: save,
+    });
+    commands.register(Command {
+        name: "reload",
+        description: "reloads the resource packs like F5, the pack list is read again from the settings",
+        arguments: vec![],
+        run: reload,
+    });
+    commands.register(Command {
+        name: "time",
+        description: "sets or shows the time of day",
+        arguments: vec![
+            ArgumentSpec::required("set|query", ArgumentKind::Literal(&["set", "query"])),
+            ArgumentSpec::optional("time", ArgumentKind::NamedInt(TIMES_OF_DAY)),
+        ],
+        run: time,
+    });
+    commands.register(Command {
+        name: "seed",
+        description: "shows the world's seed",
+        arguments: vec![],
+        run: seed,
+    });
+    commands.register(Command {
+        name: "gamemode",
+        description: "sets the player's game mode",
+        arguments: vec![ArgumentSpec::required("mode", ArgumentKind::GameMode)],
+        run: gamemode,
+    });
+    commands.register(Command {
+        name: "render_distance",
+        description: "sets or shows the render distance in chunks",
+        arguments: vec![ArgumentSpec::optional("chunks", ArgumentKind::Int)],
+        run: render_distance,
+    });
+}
+
+fn help(context: &mut CommandContext, arguments: &Arguments) -> anyhow::Result<String> {
+    if let Some(name) = arguments.word(0) {
+        let command = context.commands.get(name).ok_or_else(|| anyhow::anyhow!("unknown command '{}'", name))?;
+        return Ok(format!("{} - {}", command.usage(), command.description));
+    }
+
+    Ok(context.commands.iter().map(|command| format!("{} - {}", command.usage(), command.description)).collect::<Vec<String>>().join("\n"))
+}
+
+fn tp(context: &mut CommandContext, arguments: &Arguments) -> anyhow::Result<String> {
+    let position = arguments.position(0).unwrap();
+    context.world.player.position = position;
+    context.world.player.vertical_velocity = 0.0;
+    let position: Vector3<f64> = position.into();
+    Ok(format!("teleported to {:.1} {:.1} {:.1}", position.x, position.y, position.z))
+}
+
+fn setblock(context: &mut CommandContext, arguments: &Arguments) -> anyhow::Result<String> {
+    let position = arguments.block_position(0).unwrap();
+    let block = arguments.block(3).unwrap().clone();
+    let name = block.name().to_string();
+    if !context.world.chunk_manager.set_block(position, block) {
+        anyhow::bail!("the block isn't loaded");
+    }
+    Ok(format!("set the block to {}", name))
+}
+
+// the position given at the index, otherwise the block the player is looking at or the one they're standing in
+fn target_position(context: &CommandContext, arguments: &Arguments, index: usize) -> anyhow::Result<GlobalVecU> {
+    if arguments.get(index).is_some() {
+        return arguments.block_position(index).ok_or_else(|| anyhow::anyhow!("give all three coordinates or none"));
+    }
+    let player = &context.world.player;
+    Ok(player.raycast_block(&context.world.chunk_manager).map_or_else(|| player.position.into(), |result| result.block_position))
+}
+
+fn selection_size(context: &CommandContext) -> String {
+    match context.world.world_edit.selection.block_box() {
+        Some(block_box) => {
+            let size = block_box.size();
+            format!(", the selection is {}x{}x{} ({} blocks)", size.x, size.y, size.z, block_box.volume())
+        },
+        None => String::new(),
+    }
+}
+
+fn pos1(context: &mut CommandContext, arguments: &Arguments) -> anyhow::Result<String> {
+    let position = target_position(context, arguments, 0)?;
+    context.world.world_edit.selection.first_corner = Some(position);
+    let position = position.block_position();
+    Ok(format!("the first corner is at {} {} {}{}", position.x, position.y, position.z, selection_size(context)))
+}
+
+fn pos2(context: &mut CommandContext, arguments: &Arguments) -> anyhow::Result<String> {
+    let position = target_position(context, arguments, 0)?;
+    context.world.world_edit.selection.second_corner = Some(position);
+    let position = position.block_position();
+    Ok(format!("the second corner is at {} {} {}{}", position.x, position.y, position.z, selection_size(context)))
+}
+
+fn fill(context: &mut CommandContext, arguments: &Arguments) -> anyhow::Result<String> {
+    let block = arguments.block(0).unwrap();
+    let world = &mut *context.world;
+    let changed_blocks = world.world_edit.fill(&mut world.chunk_manager, block)?;
+    Ok(format!("filled {} blocks with {}", changed_blocks, block.name()))
+}
+
+fn replace(context: &mut CommandContext, arguments: &Arguments) -> anyhow::Result<String> {
+    let (from, to) = (arguments.block(0).unwrap(), arguments.block(1).unwrap());
+    let world = &mut *context.world;
+    let changed_blocks = world.world_edit.replace(&mut world.chunk_manager, from, to)?;
+    Ok(format!("replaced {} blocks of {} with {}", changed_blocks, from.name(), to.name()))
+}
+
+fn hollow(context: &mut CommandContext, arguments: &Arguments) -> anyhow::Result<String> {
+    let block = arguments.block(0).unwrap();
+    let world = &mut *context.world;
+    let changed_blocks = world.world_edit.hollow(&mut world.chunk_manager, block)?;
+    Ok(format!("hollowed out the selection, {} blocks changed", changed_blocks))
+}
+
+fn clone(context: &mut CommandContext, arguments: &Arguments) -> anyhow::Result<String> {
+    let destination = arguments.block_position(0).unwrap();
+    let world = &mut *context.world;
+    let changed_blocks = world.world_edit.clone_selection(&mut world.chunk_manager, destination)?;
+    Ok(format!("cloned the selection, {} blocks changed", changed_blocks))
+}
+
+// the file in the structures directory, names without an extension get the default one.
+// the name has to be a plain file name so nothing outside the directory can be read or written
+fn structure_path(name: &str, default_extension: &str) -> anyhow::Result<PathBuf> {
+    let mut components = Path::new(name).components();
+    let (Some(Component::Normal(file_name)), None) = (components.next(), components.next()) else {
+        anyhow::bail!("'{}' isn't a file name, structures can only be in {}", name, STRUCTURES_DIRECTORY);
+    };
+    let mut path = PathBuf::from(STRUCTURES_DIRECTORY).join(file_name);
+    if path.extension().is_none() {
+        path.set_extension(default_extension);
+    }
+    Ok(path)
+}
+
+fn save_selection(context: &mut CommandContext, path: PathBuf) -> anyhow::Result<String> {
+    let structure = context.world.world_edit.copy_selection(&context.world.chunk_manager)?;
+    std::fs::create_dir_all(STRUCTURES_DIRECTORY)?;
+    structure.save(&path)?;
+    Ok(format!("saved {} blocks to {}", structure.blocks().len(), path.display()))
+}
+
+// places the file's structure at the position given after its name or at the targeted block
+fn place_structure_file(context: &mut CommandContext, arguments: &Arguments, path: PathBuf) -> anyhow::Result<String> {
+    let (structure, unknown_blocks) = Structure::load(&path).map_err(|err| anyhow::anyhow!("couldn't load {}: {}", path.display(), err))?;
+    let origin = target_position(context, arguments, 2)?;
+    let world = &mut *context.world;
+    let changed_blocks = world.world_edit.place_structure(&mut world.chunk_manager, &structure, origin)?;
+    match unknown_blocks.is_empty() {
+        true => Ok(format!("placed {}, {} blocks changed", path.display(), changed_blocks)),
+        false => Ok(format!("placed {}, {} blocks changed, these blocks don't exist and were left out: {}", path.display(), changed_blocks, unknown_blocks.join(", "))),
+    }
+}
+
+fn schematic(context: &mut CommandContext, arguments: &Arguments) -> anyhow::Result<String> {
+    let path = structure_path(arguments.word(1).unwrap(), STRUCTURE_EXTENSION)?;
+    match arguments.word(0) {
+        Some("save") => save_selection(context, path),
+        _ => place_structure_file(context, arguments, path),
+    }
+}
+
+fn vox(context: &mut CommandContext, arguments: &Arguments) -> anyhow::Result<String> {
+    let path = structure_path(arguments.word(1).unwrap(), VOX_EXTENSION)?;
+    match arguments.word(0) {
+        Some("export") => save_selection(context, path),
+        _ => place_structure_file(context, arguments, path),
+    }
+}
+
+fn undo(context: &mut CommandContext, _: &Arguments) -> anyhow::Result<String> {
+    let world = &mut *context.world;
+    let changed_blocks = world.world_edit.undo(&mut world.chunk_manager).ok_or_else(|| anyhow::anyhow!("there's nothing to undo"))?;
+    Ok(format!("undid the last edit, {} blocks changed back", changed_blocks))
+}
+
+fn redo(context: &mut CommandContext, _: &Arguments) -> anyhow::Result<String> {
+    let world = &mut *context.world;
+    let changed_blocks = world.world_edit.redo(&mut world.chunk_manager).ok_or_else(|| anyhow::anyhow!("there's nothing to redo"))?;
+    Ok(format!("redid the edit, {} blocks changed", changed_blocks))
+}
+
+fn relight(context: &mut CommandContext, arguments: &Arguments) -> anyhow::Result<String> {
+    let radius = arguments.int(0).unwrap_or(0);
+    if !(0..=MAX_RELIGHT_RADIUS).contains(&radius) {
+        anyhow::bail!("the radius has to be between 0 and {}", MAX_RELIGHT_RADIUS);
+    }
+
+    let radius = radius as i32;
+    let player_chunk_position = context.world.player.position.chunk.xz();
+    let mut relit = 0;
+    let mut skipped = 0;
+    for z in -radius..=radius {
+        for x in -radius..=radius {
+            match context.world.chunk_manager.relight_chunk(player_chunk_position + Vector2::new(x, z)) {
+                true => relit += 1,
+                false => skipped += 1,
+            }
+        }
+    }
+    match skipped {
+        0 => Ok(format!("relit {} chunks", relit)),
+        _ => Ok(format!("relit {} chunks, {} didn't have all their neighbors loaded", relit, skipped)),
+    }
+}
+
+fn save(context: &mut CommandContext, _: &Arguments) -> anyhow::Result<String> {
+    let saved_chunks = context.world.save()?;
+    Ok(format!("saved the player and {} chunks", saved_chunks))
+}
+
+// the game reloads them after the command ran, failures are printed like with F5
+fn reload(context: &mut CommandContext, _: &Arguments) -> anyhow::Result<String> {
+    context.requests.reload_resource_packs = true;
+    Ok("reloading the resource packs".to_string())
+}
+
+fn time(context: &mut CommandContext, arguments: &Arguments) -> anyhow::Result<String> {
+    if arguments.word(0) == Some("set") {
+        let ticks = arguments.int(1).ok_or_else(|| anyhow::anyhow!("missing <time>, usage: time set <time>"))?;
+        context.world.time.set(ticks.rem_euclid(DAY_LENGTH as i64) as u32);
+    }
+    Ok(format!("the time is {} of {}", context.world.time.ticks(), DAY_LENGTH))
+}
+
+fn seed(_: &mut CommandContext, _: &Arguments) -> anyhow::Result<String> {
+    Ok(format!("seed: {}", WORLD_SEED))
+}
+
+fn gamemode(context: &mut CommandContext, arguments: &Arguments) -> anyhow::Result<String> {
+    let game_mode = arguments.game_mode(0).unwrap();
+    context.world.player.set_game_mode(game_mode);
+    Ok(format!("the game mode is {}", game_mode.name()))
+}
+
+fn render_distance(context: &mut CommandContext, arguments: &Arguments) -> anyhow::Result<String> {
+    if let Some(render_distance) = arguments.int(0) {
+        if !(1..=MAX_RENDER_DISTANCE).contains(&render_distance) {
+            anyhow::bail!("the render distance has to be between 1 and {}", MAX_RENDER_DISTANCE);
+        }
+        context.world.set_render_distance(render_distance as u32);
+    }
+    Ok(format!("the render distance is {} chunks", context.world.chunk_manager.render_radius()))
+}
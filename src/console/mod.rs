@@ -0,0 +1,128 @@
+use std::collections::VecDeque;
+
+use command::{CommandRegistry, CommandRequests};
+
+use crate::world::World;
+
+pub mod command;
+pub mod commands;
+
+const MAX_LOG_LINES: usize = 500;
+const MAX_HISTORY: usize = 100;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ConsoleLineKind {
+    Input,
+    Output,
+    Error,
+}
+
+pub struct ConsoleLine {
+    pub text: String,
+    pub kind: ConsoleLineKind,
+}
+
+// The developer console, it keeps the scrollback log, the typed line and the previously run lines
+pub struct Console {
+    pub is_open: bool,
+    pub input: String,
+    log: VecDeque<ConsoleLine>,
+    history: Vec<String>,
+    // the history entry that's in the input while browsing it with the arrow keys
+    history_index: Option<usize>,
+    commands: CommandRegistry,
+}
+
+impl Console {
+    pub fn new() -> Self {
+        let mut commands = CommandRegistry::new();
+        commands::register_commands(&mut commands);
+        Self {
+            is_open: false,
+            input: String::new(),
+            log: VecDeque::new(),
+            history: vec![],
+            history_index: None,
+            commands,
+        }
+    }
+
+    pub fn log(&self) -> impl Iterator<Item = &ConsoleLine> {
+        self.log.iter()
+    }
+
+    pub fn print(&mut self, kind: ConsoleLineKind, text: &str) {
+        for line in text.lines() {
+            if self.log.len() >= MAX_LOG_LINES {
+                self.log.pop_front();
+            }
+            self.log.push_back(ConsoleLine { text: line.to_string(), kind });
+        }
+    }
+
+    // runs the typed line, the returned requests are up to the caller
+    pub fn submit(&mut self, world: &mut World) -> CommandRequests {
+        let line = std::mem::take(&mut self.input);
+        self.history_index = None;
+        let mut requests = CommandRequests::default();
+        if line.trim().is_empty() { return requests; }
+
+        self.print(ConsoleLineKind::Input, &format!("> {}", line));
+        if self.history.last() != Some(&line) {
+            if self.history.len() >= MAX_HISTORY {
+                self.history.remove(0);
+            }
+            self.history.push(line.clone());
+        }
+
+        match self.commands.execute(world, &mut requests, &line) {
+            Ok(output) => self.print(ConsoleLineKind::Output, &output),
+            Err(err) => self.print(ConsoleLineKind::Error, &err.to_string()),
+        }
+        requests
+    }
+
+    // completes the last word when there's only one way to, otherwise completes what the options share and prints them
+    pub fn complete(&mut self) {
+        let completions = self.commands.completions(&self.input);
+        let Some(first) = completions.first() else { return; };
+        let shared_prefix = completions.iter().fold(first.as_str(), |prefix, completion| {
+            let shared_length = prefix.char_indices().zip(completion.chars())
+                .take_while(|((_, a), b)| a == b)
+                .last()
+                .map_or(0, |((i, a), _)| i + a.len_utf8());
+            &prefix[..shared_length]
+        }).to_string();
+
+        let word_start = self.input.rfind(char::is_whitespace).map_or(0, |i| i + 1);
+        // the slash in front of the command name is kept
+        let word_start = if word_start == 0 && self.input.starts_with('/') { 1 } else { word_start };
+        self.input.truncate(word_start);
+        self.input += &shared_prefix;
+        // block states are completed one at a time, so there's nothing to separate
+        if completions.len() == 1 && !shared_prefix.ends_with('=') {
+            self.input.push(' ');
+        }
+        if completions.len() > 1 {
+            self.print(ConsoleLineKind::Output, &completions.join("  "));
+        }
+    }
+
+    // steps through the previously run lines, older is towards the start
+    pub fn browse_history(&mut self, older: bool) {
+        if self.history.is_empty() { return; }
+        let index = match (self.history_index, older) {
+            (None, true) => self.history.len() - 1,
+            (None, false) => return,
+            (Some(index), true) => index.saturating_sub(1),
+            (Some(index), false) if index + 1 < self.history.len() => index + 1,
+            (Some(_), false) => {
+                self.history_index = None;
+                self.input.clear();
+                return;
+            },
+        };
+        self.history_index = Some(index);
+        self.input = self.history[index].clone();
+    }
+}
@@ -0,0 +1,55 @@
+use std::{path::{Path, PathBuf}, sync::mpsc::{Receiver, TryRecvError}, time::{Duration, Instant}};
+
+use notify::Watcher;
+
+// editors often write a file in a few steps, so the changes are reported once the files stop changing for this long
+const SETTLE_DURATION: Duration = Duration::from_millis(250);
+
+// Watches directories recursively and collects the files that changed and pass the filter
+pub struct DirectoryWatcher {
+    _watcher: notify::RecommendedWatcher,
+    events: Receiver<notify::Result<notify::Event>>,
+    name: &'static str,
+    filter: fn(&Path) -> bool,
+    changed_files: Vec<PathBuf>,
+    last_change: Option<Instant>,
+}
+
+impl DirectoryWatcher {
+    // the name is used in the error messages
+    pub fn new<P: AsRef<Path>>(name: &'static str, directories: &[P], filter: fn(&Path) -> bool) -> anyhow::Result<Self> {
+        let (sender, events) = std::sync::mpsc::channel();
+        let mut watcher = notify::recommended_watcher(sender)?;
+        for directory in directories {
+            watcher.watch(directory.as_ref(), notify::RecursiveMode::Recursive)?;
+        }
+
+        Ok(Self { _watcher: watcher, events, name, filter, changed_files: vec![], last_change: None })
+    }
+
+    // the files that changed, once they stayed untouched for SETTLE_DURATION
+    pub fn poll(&mut self) -> Vec<PathBuf> {
+        loop {
+            match self.events.try_recv() {
+                Ok(Ok(event)) => {
+                    if event.kind.is_access() || event.kind.is_other() { continue; }
+                    for path in event.paths.into_iter().filter(|path| (self.filter)(path)) {
+                        let path = std::fs::canonicalize(&path).unwrap_or(path);
+                        if !self.changed_files.contains(&path) { self.changed_files.push(path); }
+                        self.last_change = Some(Instant::now());
+                    }
+                },
+                Ok(Err(err)) => println!("{} watcher error: {}", self.name, err),
+                Err(TryRecvError::Empty | TryRecvError::Disconnected) => break,
+            }
+        }
+
+        match self.last_change {
+            Some(last_change) if last_change.elapsed() >= SETTLE_DURATION => {
+                self.last_change = None;
+                std::mem::take(&mut self.changed_files)
+            },
+            _ => vec![],
+        }
+    }
+}
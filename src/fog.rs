@@ -0,0 +1,90 @@
+use std::sync::Arc;
+
+use cgmath::Vector3;
+use wgpu::util::DeviceExt;
+
+// fraction of the fog's end distance at which the fog starts
+const FOG_START_FRACTION: f32 = 0.75;
+
+#[repr(C)]
+#[derive(Clone, Copy, Debug, bytemuck::Pod, bytemuck::Zeroable)]
+struct FogRaw {
+    color: [f32; 4],
+    camera_position: [f32; 3],
+    start: f32,
+    end: f32,
+    // dims the sky light and the fog's color at night
+    daylight: f32,
+    _padding: [f32; 2],
+}
+
+// Linear fog based on the horizontal distance from the camera
+#[derive(Clone)]
+pub struct Fog {
+    color: wgpu::Color,
+    fog: FogRaw,
+    buffer: Arc<wgpu::Buffer>,
+}
+
+impl Fog {
+    pub fn new(device: &wgpu::Device, color: wgpu::Color) -> Self {
+        let fog = FogRaw {
+            color: [color.r as f32, color.g as f32, color.b as f32, color.a as f32],
+            camera_position: [0.0; 3],
+            start: f32::MAX,
+            end: f32::MAX,
+            daylight: 1.0,
+            _padding: [0.0; 2],
+        };
+
+        let buffer = device.create_buffer_init(&wgpu::util::BufferInitDescriptor {
+            label: Some("Fog_buffer"),
+            contents: bytemuck::bytes_of(&fog),
+            usage: wgpu::BufferUsages::UNIFORM | wgpu::BufferUsages::COPY_DST
+        });
+
+        Self { color, fog, buffer: Arc::new(buffer) }
+    }
+
+    pub fn update(&mut self, camera_position: Vector3<f32>, end: f32) {
+        self.fog.camera_position = camera_position.into();
+        self.fog.start = end * FOG_START_FRACTION;
+        self.fog.end = end;
+    }
+
+    pub fn set_daylight(&mut self, daylight: f32) {
+        self.fog.daylight = daylight;
+        let color = self.color();
+        self.fog.color = [color.r as f32, color.g as f32, color.b as f32, color.a as f32];
+    }
+
+    // the color darkened by the daylight, the sky is cleared with it too
+    pub fn color(&self) -> wgpu::Color {
+        let daylight = self.fog.daylight as f64;
+        wgpu::Color { r: self.color.r * daylight, g: self.color.g * daylight, b: self.color.b * daylight, a: self.color.a }
+    }
+
+    pub fn update_buffer(&self, queue: &wgpu::Queue) {
+        queue.write_buffer(&self.buffer, 0, bytemuck::bytes_of(&self.fog));
+    }
+
+    pub fn bind_group_layout_entry(&self, binding: u32) -> wgpu::BindGroupLayoutEntry {
+        wgpu::BindGroupLayoutEntry {
+            binding,
+            count: None,
+            ty: wgpu::BindingType::Buffer {
+                has_dynamic_offset: false,
+                min_binding_size: None,
+                ty: wgpu::BufferBindingType::Uniform,
+            },
+            visibility: wgpu::ShaderStages::VERTEX_FRAGMENT,
+        }
+    }
+
+    pub fn bind_group_entry(&self, binding: u32) -> wgpu::BindGroupEntry<'_> {
+        wgpu::BindGroupEntry {
+            binding,
+            resource: self.buffer.as_entire_binding(),
+        }
+    }
+}
@@ -2,9 +2,9 @@ use std::{io::Read, sync::Arc};
 
 use egui::Color32;
 use wgpu::{Device, Features, Queue};
-use winit::{event::Event, event_loop::EventLoop};
+use winit::event_loop::EventLoop;
 
-use crate::{event::EventManager, game_window::{GameWindow, GameWindowEvent, KeyboardInputEvent, MouseInputEvent, MouseMoveEvent}, layer::{chunk_rendering_layer::ChunkRenderingLayer, game_logic_layer::{ChunkUpdateRenderMesh, GameLogicLayer}, game_window_layer::GameWindowLayer, LayerStack}, render_thread::RenderThread, settings::Settings, GLOBAL_RESOURCES};
+use crate::{block::{asset_diagnostics::AssetDiagnostics, asset_loader::load_assets, resource_pack::{ResourcePackStack, BASE_RESOURCE_PACK_PATH}}, event::EventManager, game_window::GameWindow, layer::{chunk_rendering_layer::ChunkRenderingLayer, game_logic_layer::GameLogicLayer, game_window_layer::GameWindowLayer, LayerStack}, render_thread::RenderThread, settings::Settings, set_assets, GLOBAL_RESOURCES};
 
 pub struct Game {
     pub game_window: GameWindow,
@@ -23,6 +23,8 @@ pub struct Game {
     pub render_thread: RenderThread,
     pub quit: bool,
     pub is_render_frame: bool,
+    // the cursor is hidden and kept in the middle of the window while it controls the camera
+    pub cursor_captured: bool,
 
     pub egui_winit_state: egui_winit::State,
     pub egui_full_output: egui::FullOutput,
@@ -125,6 +127,7 @@ impl Game {
                 render_thread,
                 quit: false,
                 is_render_frame: false,
+                cursor_captured: true,
                 egui_winit_state,
                 egui_full_output: egui::FullOutput::default(),
                 last_render_instant: std::time::Instant::now(),
@@ -155,6 +158,9 @@ impl Game {
         let settings_path = settings_path.into();
         let (mut game, event_loop) = pollster::block_on(Self::new(&settings_path))?;
         let event_manager = (*GLOBAL_RESOURCES).get::<EventManager>().unwrap();
+
+        let resource_packs = ResourcePackStack::new(BASE_RESOURCE_PACK_PATH, &game.settings.resource_packs)?;
+        set_assets(AssetDiagnostics::load(|diagnostics| load_assets(&resource_packs, None, diagnostics))?);
         
         let mut layers = LayerStack::new();
 
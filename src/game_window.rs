@@ -74,4 +74,10 @@ pub struct MouseInputEvent {
 #[derive(Clone)]
 pub struct MouseMoveEvent {
     pub delta: Vector2<f64>,
+}
+
+// scrolled lines, positive when scrolling up
+#[derive(Clone)]
+pub struct MouseScrollEvent {
+    pub delta: f32,
 }
\ No newline at end of file
@@ -430,6 +430,12 @@ impl GlobalVecU {
         self.chunk.y > 0 && self.chunk.y < CHUNK_SIZE_I32
     }
 
+    // the position in blocks counted from the world's origin, the inverse of From<Vector3<i32>>
+    #[inline]
+    pub fn block_position(&self) -> Vector3<i32> {
+        self.chunk * CHUNK_SIZE_I32 + self.local
+    }
+
     #[inline]
     pub fn touching_sides(&self) -> Option<Vector3<i32>> {
         const CHUNK_SIZE_I32_MIN_ONE: i32 = CHUNK_SIZE_I32 - 1;
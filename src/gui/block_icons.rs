@@ -0,0 +1,93 @@
+use cgmath::{InnerSpace, Vector2, Vector3};
+use hashbrown::HashMap;
+
+use crate::block::{asset_loader::Assets, Block, BlockId, FACE_DIRECTIONS_NUM};
+
+pub const BLOCK_ICON_SIZE: usize = 32;
+
+// Icons of the blocks for the gui, they're rendered when they're first needed and thrown away when the assets are reloaded,
+// blocks with different states get their own icons
+pub struct BlockIcons {
+    icons: HashMap<(BlockId, String), egui::TextureHandle>,
+}
+
+impl BlockIcons {
+    pub fn new() -> Self {
+        Self { icons: HashMap::new() }
+    }
+
+    pub fn clear(&mut self) {
+        self.icons.clear();
+    }
+
+    pub fn get(&mut self, ctx: &egui::Context, assets: &Assets, block: &Block) -> egui::TextureId {
+        self.icons.entry((block.id(), block.block_state.to_string())).or_insert_with(|| {
+            let image = render_block_icon(assets, block, BLOCK_ICON_SIZE);
+            ctx.load_texture(format!("block_icon_{}", block.name()), image, egui::TextureOptions::NEAREST)
+        }).id()
+    }
+}
+
+// draws the block's quads as seen from above its south east corner,
+// the quads are parallelograms so every pixel is mapped back onto its quad to find the uv
+pub fn render_block_icon(assets: &Assets, block: &Block, size: usize) -> egui::ColorImage {
+    let mut image = egui::ColorImage::new([size, size], egui::Color32::TRANSPARENT);
+    let mut depth_buffer = vec![f32::NEG_INFINITY; size * size];
+    let Some(models) = assets.block_model_variants.get_quad_block_models(block) else { return image; };
+
+    let view_direction = Vector3::new(1.0, 1.0, 1.0).normalize();
+    let right = Vector3::new(1.0, 0.0, -1.0).normalize();
+    let up = Vector3::new(-1.0, 2.0, -1.0).normalize();
+    let scale = size as f32 / 1.7;
+    let project = |position: Vector3<f32>| {
+        let position = position - Vector3::new(0.5, 0.5, 0.5);
+        (Vector2::new(size as f32 / 2.0 + position.dot(right) * scale, size as f32 / 2.0 - position.dot(up) * scale), position.dot(view_direction))
+    };
+
+    for model in models.iter() {
+        for face_num in 0..FACE_DIRECTIONS_NUM {
+            for (quad_index, texture_index) in model.quad_indices_per_face[face_num].iter().zip(model.texture_indices_per_face[face_num].iter()) {
+                let quad = &assets.quads[*quad_index as usize];
+                let normal = Vector3::new(quad.normal[0], quad.normal[1], quad.normal[2]);
+                if normal.dot(view_direction) <= 0.0 { continue; }
+                let Some(texture) = assets.texture_images.get(*texture_index as usize) else { continue; };
+
+                let vertices = quad.vertex_positions.map(|f| project(Vector3::new(f[0], f[1], f[2])));
+                let uv = quad.uv.map(Vector2::from);
+                let (origin, origin_depth) = vertices[0];
+                let u_edge = vertices[1].0 - origin;
+                let v_edge = vertices[2].0 - origin;
+                let determinant = u_edge.x * v_edge.y - u_edge.y * v_edge.x;
+                if determinant.abs() <= f32::EPSILON { continue; }
+
+                // the sides are darker than the top like in the world
+                let shade = 0.6 + 0.4 * normal.y.max(0.0) + 0.2 * normal.x.max(0.0);
+                let min = vertices.iter().fold(Vector2::new(f32::INFINITY, f32::INFINITY), |min, (f, _)| min.zip(*f, f32::min));
+                let max = vertices.iter().fold(Vector2::new(f32::NEG_INFINITY, f32::NEG_INFINITY), |max, (f, _)| max.zip(*f, f32::max));
+                for y in (min.y.floor().max(0.0) as usize)..(max.y.ceil().min(size as f32) as usize) {
+                    for x in (min.x.floor().max(0.0) as usize)..(max.x.ceil().min(size as f32) as usize) {
+                        let offset = Vector2::new(x as f32 + 0.5, y as f32 + 0.5) - origin;
+                        let a = (offset.x * v_edge.y - offset.y * v_edge.x) / determinant;
+                        let b = (u_edge.x * offset.y - u_edge.y * offset.x) / determinant;
+                        if !(0.0..=1.0).contains(&a) || !(0.0..=1.0).contains(&b) { continue; }
+
+                        let depth = origin_depth + a * (vertices[1].1 - origin_depth) + b * (vertices[2].1 - origin_depth);
+                        if depth <= depth_buffer[y * size + x] { continue; }
+
+                        let texture_uv = uv[0] + (uv[1] - uv[0]) * a + (uv[2] - uv[0]) * b;
+                        let texel_x = ((texture_uv.x * texture.width() as f32) as u32).min(texture.width() - 1);
+                        let texel_y = ((texture_uv.y * texture.height() as f32) as u32).min(texture.height() - 1);
+                        let [red, green, blue, alpha] = texture.get_pixel(texel_x, texel_y).0;
+                        if alpha < 128 { continue; }
+
+                        depth_buffer[y * size + x] = depth;
+                        let shade = |f: u8| (f as f32 * shade).min(255.0) as u8;
+                        image.pixels[y * size + x] = egui::Color32::from_rgb(shade(red), shade(green), shade(blue));
+                    }
+                }
+            }
+        }
+    }
+
+    image
+}
@@ -0,0 +1,73 @@
+use egui::{Color32, RichText};
+
+use crate::console::{Console, ConsoleLineKind};
+
+// what was done in the console this frame
+#[derive(Debug, Default, Clone, Copy)]
+pub struct ConsoleGuiResponse {
+    pub submitted: bool,
+}
+
+// The console's log with the input line under it, tab completes the line and the arrow keys go through the history
+pub struct ConsoleGui;
+
+impl ConsoleGui {
+    pub fn show(ctx: &egui::Context, console: &mut Console) -> ConsoleGuiResponse {
+        let mut response = ConsoleGuiResponse::default();
+        egui::Window::new("console")
+        .collapsible(false)
+        .default_size(egui::vec2(640.0, 360.0))
+        .anchor(egui::Align2::CENTER_TOP, egui::vec2(0.0, 8.0))
+        .show(ctx, |ui| {
+            egui::ScrollArea::vertical()
+            .max_height(320.0)
+            .auto_shrink([false, false])
+            .stick_to_bottom(true)
+            .show(ui, |ui| {
+                for line in console.log() {
+                    let color = match line.kind {
+                        ConsoleLineKind::Input => Color32::GRAY,
+                        ConsoleLineKind::Output => Color32::WHITE,
+                        ConsoleLineKind::Error => Color32::RED,
+                    };
+                    ui.label(RichText::new(&line.text).monospace().color(color));
+                }
+            });
+
+            let input_id = egui::Id::new("console_input");
+            let (tab, enter, up, down) = ui.input(|i| (i.key_pressed(egui::Key::Tab), i.key_pressed(egui::Key::Enter), i.key_pressed(egui::Key::ArrowUp), i.key_pressed(egui::Key::ArrowDown)));
+            let input = ui.add(
+                egui::TextEdit::singleline(&mut console.input)
+                .id(input_id)
+                .font(egui::TextStyle::Monospace)
+                .desired_width(f32::INFINITY)
+                .lock_focus(true)
+            );
+            // the key that opens the console gets typed into it
+            console.input.retain(|c| c != '`' && c != '\t');
+
+            let mut move_cursor_to_end = false;
+            if input.has_focus() && tab {
+                console.complete();
+                move_cursor_to_end = true;
+            }
+            if input.has_focus() && (up || down) {
+                console.browse_history(up);
+                move_cursor_to_end = true;
+            }
+            if input.lost_focus() && enter {
+                response.submitted = true;
+            }
+            if move_cursor_to_end {
+                if let Some(mut state) = egui::TextEdit::load_state(ctx, input_id) {
+                    let end = egui::text::CCursor::new(console.input.chars().count());
+                    state.cursor.set_char_range(Some(egui::text::CCursorRange::one(end)));
+                    state.store(ctx, input_id);
+                }
+            }
+            input.request_focus();
+        });
+
+        response
+    }
+}
@@ -0,0 +1,80 @@
+use egui::{Color32, RichText};
+
+use crate::{block::Block, world::hotbar::{Hotbar, HOTBAR_SLOTS}, assets};
+
+use super::block_icons::BlockIcons;
+
+const SLOT_SIZE: f32 = 48.0;
+
+// The hotbar's slots at the bottom of the screen, the selected one is outlined
+pub struct HotbarGui<'a> {
+    pub hotbar: &'a Hotbar,
+}
+
+impl<'a> HotbarGui<'a> {
+    pub fn new(hotbar: &'a Hotbar) -> Self {
+        Self { hotbar }
+    }
+
+    pub fn show(&self, ctx: &egui::Context, block_icons: &mut BlockIcons) {
+        let assets = assets();
+        egui::Area::new(egui::Id::new("hotbar"))
+        .anchor(egui::Align2::CENTER_BOTTOM, egui::vec2(0.0, -8.0))
+        .interactable(false)
+        .show(ctx, |ui| {
+            ui.horizontal(|ui| {
+                for slot in 0..HOTBAR_SLOTS {
+                    let stroke = if slot == self.hotbar.selected_slot() {
+                        egui::Stroke::new(3.0_f32, Color32::WHITE)
+                    } else {
+                        egui::Stroke::new(1.0_f32, Color32::DARK_GRAY)
+                    };
+                    egui::Frame::none()
+                    .fill(Color32::from_black_alpha(128))
+                    .stroke(stroke)
+                    .inner_margin(4.0)
+                    .show(ui, |ui| {
+                        let icon_size = egui::vec2(SLOT_SIZE, SLOT_SIZE);
+                        match self.hotbar.slot(slot).and_then(|item| item.block()) {
+                            Some(block) => { ui.image((block_icons.get(ctx, &assets, &block), icon_size)); },
+                            None => { ui.allocate_space(icon_size); },
+                        }
+                    });
+                }
+            });
+        });
+    }
+}
+
+// A window with every loaded block, clicking one puts it in the hotbar's selected slot
+pub struct BlockPickerGui;
+
+impl BlockPickerGui {
+    // returns the name of the clicked block
+    pub fn show(ctx: &egui::Context, block_icons: &mut BlockIcons) -> Option<String> {
+        let assets = assets();
+        let mut picked_block = None;
+        egui::Window::new("blocks")
+        .resizable(false)
+        .collapsible(false)
+        .anchor(egui::Align2::CENTER_CENTER, egui::vec2(0.0, 0.0))
+        .show(ctx, |ui| {
+            ui.label(RichText::new("pick a block for the selected slot").size(16.0).color(Color32::WHITE));
+            egui::Grid::new("block_picker_grid").spacing(egui::vec2(4.0, 4.0)).show(ui, |ui| {
+                for (i, block_info) in assets.block_list.iter().enumerate() {
+                    let block: Block = block_info.clone().into();
+                    let icon = block_icons.get(ctx, &assets, &block);
+                    let button = egui::ImageButton::new((icon, egui::vec2(SLOT_SIZE, SLOT_SIZE)));
+                    if ui.add(button).on_hover_text(block.name()).clicked() {
+                        picked_block = Some(block.name().to_string());
+                    }
+                    if (i + 1) % HOTBAR_SLOTS == 0 {
+                        ui.end_row();
+                    }
+                }
+            });
+        });
+
+        picked_block
+    }
+}
@@ -0,0 +1,172 @@
+use cgmath::{Deg, Rad, Vector2};
+use egui::{Color32, Stroke};
+use hashbrown::HashMap;
+
+use crate::{block::Block, global_vector::GlobalVecF, world::{chunk::{chunk_generator::GenerationStage, chunk_map::ChunkMap, chunk_part::{CHUNK_SIZE, CHUNK_SIZE_I32}, Chunk}, map::{map_column, shade, surface_block}}};
+
+const MINIMAP_SIZE: f32 = 192.0;
+// screen pixels per block
+const ZOOM_LEVELS: [f32; 4] = [0.5, 1.0, 2.0, 4.0];
+const DEFAULT_ZOOM_LEVEL: usize = 1;
+// the other tiles that changed wait for the next frames
+const MAX_TILE_UPDATES_PER_FRAME: usize = 16;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MinimapMode {
+    NorthUp,
+    // the player always faces up
+    RotateWithPlayer,
+}
+
+impl MinimapMode {
+    pub fn next(self) -> Self {
+        match self {
+            Self::NorthUp => Self::RotateWithPlayer,
+            Self::RotateWithPlayer => Self::NorthUp,
+        }
+    }
+}
+
+// A chunk's columns as a texture, it's drawn again when the chunk or the chunk north of it changes because that one shades its first row
+struct MinimapTile {
+    texture: egui::TextureHandle,
+    last_update: u64,
+    north_last_update: Option<u64>,
+}
+
+// The loaded chunks around the player seen from above in the top right corner, the player is in the middle
+pub struct MinimapGui {
+    tiles: HashMap<Vector2<i32>, MinimapTile>,
+    pub mode: MinimapMode,
+    zoom_level: usize,
+}
+
+impl MinimapGui {
+    pub fn new() -> Self {
+        Self {
+            tiles: HashMap::new(),
+            mode: MinimapMode::NorthUp,
+            zoom_level: DEFAULT_ZOOM_LEVEL,
+        }
+    }
+
+    // the tiles are drawn again, e.g. when the map colors changed with the assets
+    pub fn clear(&mut self) {
+        self.tiles.clear();
+    }
+
+    pub fn zoom_in(&mut self) {
+        self.zoom_level = (self.zoom_level + 1).min(ZOOM_LEVELS.len() - 1);
+    }
+
+    pub fn zoom_out(&mut self) {
+        self.zoom_level = self.zoom_level.saturating_sub(1);
+    }
+
+    #[inline]
+    fn scale(&self) -> f32 {
+        ZOOM_LEVELS[self.zoom_level]
+    }
+
+    // how many chunks away from the player's chunk can be seen, the corners are further away when the map is rotated
+    fn visible_radius(&self) -> i32 {
+        (MINIMAP_SIZE * std::f32::consts::FRAC_1_SQRT_2 / self.scale() / CHUNK_SIZE as f32).ceil() as i32 + 1
+    }
+
+    // draws the tiles of the chunks that changed since they were last drawn, the closest first,
+    // and throws away the ones that can't be seen anymore
+    pub fn update(&mut self, ctx: &egui::Context, chunk_map: &ChunkMap, center: Vector2<i32>) {
+        let radius = self.visible_radius();
+        self.tiles.retain(|position, _| (position.x - center.x).abs() <= radius && (position.y - center.y).abs() <= radius && chunk_map.contains_position(position));
+
+        let mut offsets = (-radius..=radius).flat_map(|z| (-radius..=radius).map(move |x| Vector2::new(x, z))).collect::<Vec<Vector2<i32>>>();
+        offsets.sort_unstable_by_key(|offset| offset.x * offset.x + offset.y * offset.y);
+
+        let mut updates = 0;
+        for offset in offsets {
+            if updates >= MAX_TILE_UPDATES_PER_FRAME { break; }
+            let position = center + offset;
+            let Some(chunk) = chunk_map.borrow_chunk(&position).filter(|chunk| chunk.generation_stage == GenerationStage::LAST_GENERATION_STAGE) else { continue; };
+            let north_chunk = chunk_map.borrow_chunk(&(position - Vector2::new(0, 1))).filter(|chunk| chunk.generation_stage == GenerationStage::LAST_GENERATION_STAGE);
+            let north_last_update = north_chunk.map(|north_chunk| north_chunk.last_update);
+            if self.tiles.get(&position).is_some_and(|tile| tile.last_update == chunk.last_update && tile.north_last_update == north_last_update) { continue; }
+
+            let image = render_tile(chunk, north_chunk);
+            match self.tiles.get_mut(&position) {
+                Some(tile) => {
+                    tile.texture.set(image, egui::TextureOptions::NEAREST);
+                    tile.last_update = chunk.last_update;
+                    tile.north_last_update = north_last_update;
+                },
+                None => {
+                    let texture = ctx.load_texture(format!("minimap_tile_{}_{}", position.x, position.y), image, egui::TextureOptions::NEAREST);
+                    self.tiles.insert(position, MinimapTile { texture, last_update: chunk.last_update, north_last_update });
+                },
+            }
+            updates += 1;
+        }
+    }
+
+    pub fn show(&self, ctx: &egui::Context, position: GlobalVecF, yaw: Deg<f32>) {
+        let (sin_yaw, cos_yaw) = Rad::from(yaw).0.sin_cos();
+        let forward = Vector2::new(cos_yaw, sin_yaw);
+        // x goes right and north (-z) goes up, or the player's forward goes up
+        let rotate = |offset: Vector2<f32>| match self.mode {
+            MinimapMode::NorthUp => egui::vec2(offset.x, offset.y),
+            MinimapMode::RotateWithPlayer => egui::vec2(offset.y * forward.x - offset.x * forward.y, -(offset.x * forward.x + offset.y * forward.y)),
+        };
+        let player_offset = Vector2::new(position.local().x, position.local().z);
+        let scale = self.scale();
+
+        egui::Area::new(egui::Id::new("minimap"))
+        .anchor(egui::Align2::RIGHT_TOP, egui::vec2(-8.0, 8.0))
+        .interactable(false)
+        .show(ctx, |ui| {
+            let (rect, _) = ui.allocate_exact_size(egui::vec2(MINIMAP_SIZE, MINIMAP_SIZE), egui::Sense::hover());
+            let painter = ui.painter_at(rect);
+            let center = rect.center();
+            painter.rect_filled(rect, 0.0, Color32::from_black_alpha(128));
+
+            for (tile_position, tile) in self.tiles.iter() {
+                let corner = (tile_position - position.chunk.xz()).map(|f| (f * CHUNK_SIZE_I32) as f32) - player_offset;
+                let mut mesh = egui::Mesh::with_texture(tile.texture.id());
+                for (x, z) in [(0.0, 0.0), (1.0, 0.0), (1.0, 1.0), (0.0, 1.0)] {
+                    let pos = center + rotate(corner + Vector2::new(x, z) * CHUNK_SIZE as f32) * scale;
+                    mesh.vertices.push(egui::epaint::Vertex { pos, uv: egui::pos2(x, z), color: Color32::WHITE });
+                }
+                mesh.add_triangle(0, 1, 2);
+                mesh.add_triangle(0, 2, 3);
+                painter.add(egui::Shape::mesh(mesh));
+            }
+
+            let direction = rotate(forward);
+            let side = egui::vec2(-direction.y, direction.x);
+            painter.add(egui::Shape::convex_polygon(
+                vec![center + direction * 8.0, center - direction * 5.0 + side * 5.0, center - direction * 5.0 - side * 5.0],
+                Color32::WHITE,
+                Stroke::new(1.0_f32, Color32::BLACK),
+            ));
+            let north = rotate(Vector2::new(0.0, -1.0));
+            painter.text(center + north * (MINIMAP_SIZE / 2.0 - 10.0), egui::Align2::CENTER_CENTER, "N", egui::FontId::proportional(14.0), Color32::WHITE);
+            painter.rect_stroke(rect, 0.0, Stroke::new(1.0_f32, Color32::DARK_GRAY));
+        });
+    }
+}
+
+// one pixel per column, a column that's only air is transparent
+fn render_tile(chunk: &Chunk, north_chunk: Option<&Chunk>) -> egui::ColorImage {
+    let mut image = egui::ColorImage::new([CHUNK_SIZE, CHUNK_SIZE], Color32::TRANSPARENT);
+    let map_color = |block: &Block| block.properties().map_color;
+    for z in 0..CHUNK_SIZE as u8 {
+        for x in 0..CHUNK_SIZE as u8 {
+            let Some(column) = map_column(chunk, Vector2::new(x, z), map_color) else { continue; };
+            let north_height = match z {
+                0 => north_chunk.and_then(|north_chunk| surface_block(north_chunk, Vector2::new(x, CHUNK_SIZE as u8 - 1))),
+                _ => surface_block(chunk, Vector2::new(x, z - 1)),
+            }.map(|(height, _)| height);
+            let [r, g, b] = shade(column, north_height);
+            image.pixels[x as usize + z as usize * CHUNK_SIZE] = Color32::from_rgb(r, g, b);
+        }
+    }
+    image
+}
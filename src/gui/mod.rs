@@ -1,27 +1,74 @@
 use cgmath::Vector3;
 use egui::{Color32, RichText, Ui};
 
-use crate::{block::light::LightLevel, global_vector::GlobalVecF, world::World};
+use crate::{block::{block_pallet::BlockPalletItemId, light::LightLevel, Block}, global_vector::{GlobalVecF, GlobalVecU}, world::{chunk::chunk_map::ChunkMap, game_mode::GameMode, raycast::BlockRaycastResult, spawn::Spawn, World}};
 
 pub mod egui_renderer;
+pub mod block_icons;
+pub mod hotbar_gui;
+pub mod console_gui;
+pub mod minimap_gui;
+
+// what was clicked in the debug gui this frame
+#[derive(Debug, Default, Clone, Copy)]
+pub struct DebugGuiResponse {
+    pub reload_resource_packs: bool,
+    pub game_mode: Option<GameMode>,
+    pub set_spawn: bool,
+}
+
+// what the debug gui shows about the block under the crosshair
+pub struct TargetedBlockInfo {
+    pub block: Block,
+    pub position: GlobalVecU,
+    pub light_level: Option<LightLevel>,
+    // the light of the block in front of the targeted face, it's what lights the face
+    pub face_light_level: Option<LightLevel>,
+    pub block_pallet_id: Option<BlockPalletItemId>,
+}
+
+impl TargetedBlockInfo {
+    pub fn new(chunk_map: &ChunkMap, target: &BlockRaycastResult) -> Self {
+        Self {
+            block: target.block.clone(),
+            position: target.block_position,
+            light_level: chunk_map.get_light_level(target.block_position),
+            face_light_level: chunk_map.get_light_level(target.block_position + target.face.normal_i32()),
+            block_pallet_id: chunk_map.get_block_pallet_id(target.block_position),
+        }
+    }
+}
+
 pub struct DebugGui {
     pub position: GlobalVecF,
     pub light_level: LightLevel,
+    pub target: Option<TargetedBlockInfo>,
+    pub game_mode: GameMode,
+    pub instant_break: bool,
+    pub spawn: Spawn,
     pub last_frame_time: std::time::Duration,
     pub last_update_time: std::time::Duration,
+    pub shader_errors: Vec<String>,
 }
 
 impl DebugGui {
-    pub fn new(world: &World, last_frame_time: std::time::Duration, last_update_time: std::time::Duration) -> Self {
+    pub fn new(world: &World, target: Option<&BlockRaycastResult>, last_frame_time: std::time::Duration, last_update_time: std::time::Duration, shader_errors: Vec<String>) -> Self {
+        let chunk_map = world.chunk_manager.chunk_map_lock.read();
         Self {
+            target: target.map(|target| TargetedBlockInfo::new(&chunk_map, target)),
+            game_mode: world.player.game_mode,
+            instant_break: world.player.instant_break,
+            spawn: world.spawn,
             position: world.player.position,
-            light_level: world.chunk_manager.chunk_map_lock.read().get_light_level(world.player.position.into()).unwrap_or(LightLevel::new(0, 0).unwrap()),
+            light_level: chunk_map.get_light_level(world.player.position.into()).unwrap_or(LightLevel::new(0, 0).unwrap()),
             last_frame_time,
             last_update_time,
+            shader_errors,
         }
     }
 
-    pub fn show(&self, ctx: &egui::Context) {
+    pub fn show(&self, ctx: &egui::Context) -> DebugGuiResponse {
+        let mut response = DebugGuiResponse::default();
         #[inline]
         fn add_label(ui: &mut Ui, text: String) {
             ui.label(RichText::new(text).size(16.0).color(Color32::WHITE));
@@ -41,11 +88,46 @@ impl DebugGui {
             add_label(ui, format!("light_level:  block: {: <2}   sky: {: <2}", self.light_level.get_block(), self.light_level.get_sky()));
             add_label(ui, format!("fps: {: <3}   mpf: {: <4.1}", (1.0 / self.last_frame_time.as_secs_f32()).floor() as u32, self.last_frame_time.as_secs_f32() * 1000.0));
             add_label(ui, format!("last_update_time: {: <4.1} ms", self.last_update_time.as_secs_f64() * 1000.0));
+            ui.horizontal(|ui| {
+                add_label(ui, "game_mode:".to_string());
+                for game_mode in GameMode::ALL {
+                    if ui.selectable_label(game_mode == self.game_mode, RichText::new(game_mode.name()).size(16.0)).clicked() {
+                        response.game_mode = Some(game_mode);
+                    }
+                }
+            });
+            add_label(ui, format!("instant_break: {}", self.instant_break));
+            ui.horizontal(|ui| {
+                match self.spawn.position() {
+                    Some(position) => add_label(ui, format!("spawn:  x: {: <4} y: {: <4} z: {: <4}", position.x, position.y, position.z)),
+                    None => add_label(ui, "spawn: searching".to_string()),
+                }
+                response.set_spawn = ui.button(RichText::new("set spawn here").size(16.0)).clicked();
+            });
+            if let Some(target) = &self.target {
+                let format_light_level = |light_level: Option<LightLevel>| match light_level {
+                    Some(light_level) => format!("block: {: <2} sky: {: <2}", light_level.get_block(), light_level.get_sky()),
+                    None => "-".to_string(),
+                };
+                add_label(ui, format!("target: {}", target.block.name()));
+                add_label(ui, format!("target_state: [{}]", target.block.block_state));
+                add_label(ui, format!("target_properties: {:?}", target.block.properties()));
+                add_label(ui, format!("target_light_level:  {}   face: {}", format_light_level(target.light_level), format_light_level(target.face_light_level)));
+                add_label(ui, format!("target_pallet_id: {}", target.block_pallet_id.map_or("-".to_string(), |f| f.to_string())));
+                let (chunk, local) = (target.position.chunk, target.position.local());
+                add_label(ui, format!("target_chunk_part:  x: {: <4} y: {: <4} z: {: <4}   local:  x: {: <2} y: {: <2} z: {: <2}", chunk.x, chunk.y, chunk.z, local.x, local.y, local.z));
+            }
+            response.reload_resource_packs = ui.button(RichText::new("reload resource packs").size(16.0)).clicked();
+            for shader_error in self.shader_errors.iter() {
+                ui.label(RichText::new(shader_error).size(16.0).color(Color32::RED));
+            }
         });
         egui::CentralPanel::default().show(ctx, |ui| {
             ui.centered_and_justified(|center| {
                 center.add(egui::Label::new(egui::RichText::new("+").color(Color32::DARK_GRAY).size(32.0).monospace()).selectable(false));
             });
         });
+
+        response
     }
 }
@@ -1,21 +1,39 @@
 use std::sync::Arc;
 
 use cgmath::{Vector2, Vector3};
+use winit::{event::MouseButton, keyboard::KeyCode};
 
-use crate::{camera::Camera, event::{EventManager, EventReader}, game::Game, game_window::{KeyboardInputEvent, MouseInputEvent, MouseMoveEvent}, global_vector::GlobalVecU, gui::DebugGui, interval::Interval, layer::Layer, settings::Settings, world::{chunk::{chunk_part::CHUNK_SIZE_I32, chunks3x3::Chunks3x3, dynamic_chunk_mesh::DynamicChunkMesh}, region::Region, World, PARTS_PER_CHUNK}, BLOCK_MAP, GLOBAL_RESOURCES};
+use crate::{console::Console, block::{asset_diagnostics::AssetDiagnostics, asset_loader::load_assets, asset_watcher::AssetWatcher, resource_pack::{ResourcePackStack, BASE_RESOURCE_PACK_PATH}}, camera::Camera, event::{EventManager, EventReader}, game::Game, game_window::{KeyboardInputEvent, MouseInputEvent, MouseMoveEvent, MouseScrollEvent}, global_vector::GlobalVecU, gui::{block_icons::BlockIcons, console_gui::ConsoleGui, hotbar_gui::{BlockPickerGui, HotbarGui}, minimap_gui::MinimapGui, DebugGui}, interval::Interval, layer::Layer, settings::Settings, shader::ShaderManager, world::{chunk::{chunk_part::CHUNK_SIZE_I32, chunks3x3::Chunks3x3, dynamic_chunk_mesh::DynamicChunkMesh}, hotbar::HotbarItem, player::{PLAYER_AUTOSAVE_INTERVAL, PLAYER_SAVE_PATH}, region::Region, World, PARTS_PER_CHUNK}, GLOBAL_RESOURCES, assets, set_assets};
 
 pub struct GameLogicLayer {
     world: World,
     interval_300hz: Interval,
     interval_60hz: Interval,
     interval_20hz: Interval,
+    autosave_interval: Interval,
     keyboard_input_reader: EventReader<KeyboardInputEvent>,
     mouse_input_reader: EventReader<MouseInputEvent>,
     mouse_move_reader: EventReader<MouseMoveEvent>,
+    mouse_scroll_reader: EventReader<MouseScrollEvent>,
+    block_icons: BlockIcons,
+    is_block_picker_open: bool,
+    console: Console,
+    minimap: MinimapGui,
+    asset_watcher: Option<AssetWatcher>,
+    shader_manager: Option<ShaderManager>,
 }
 
 impl Layer for GameLogicLayer {
     fn on_update(&mut self, event_manager: &EventManager, game: &mut Game) {
+        if self.asset_watcher.as_mut().is_some_and(|asset_watcher| asset_watcher.poll()) {
+            self.reload_resource_packs(game);
+        }
+
+        let changed_shader_files = self.shader_manager.as_mut().map(|shader_manager| shader_manager.poll()).unwrap_or_default();
+        if !changed_shader_files.is_empty() {
+            self.reload_shaders(game, &changed_shader_files);
+        }
+
         self.interval_300hz.tick(|| {
             self.world.chunk_manager.update(&game.device);
         });
@@ -24,7 +42,8 @@ impl Layer for GameLogicLayer {
         self.interval_20hz.tick(|| {
             let now = std::time::Instant::now();
             self.world.chunk_manager.insert_chunks_around_player(self.world.player.position.chunk.xz());
-            self.world.player.modify_block(&mut self.world.chunk_manager, BLOCK_MAP.get("torch").unwrap().clone().into());
+            self.world.update_spawn();
+            self.world.player.modify_block(&mut self.world.chunk_manager);
             while let Some(changed_block_position) = self.world.chunk_manager.changed_blocks.pop() {
                 let mut inner_chunk_position = changed_block_position.local().map(|f| f as i32);
                 inner_chunk_position.y += changed_block_position.chunk.y * CHUNK_SIZE_I32;
@@ -90,27 +109,112 @@ impl Layer for GameLogicLayer {
             game.last_update_time = now.elapsed();
         });
 
+        let mut reload_resource_packs = false;
         for event in self.keyboard_input_reader.read() {
+            if event.key_code == KeyCode::Backquote && event.pressed && !event.repeat {
+                self.console.is_open = !self.console.is_open;
+                continue;
+            }
+            // the keys are typed into the console, releasing them still stops the player
+            if self.console.is_open && event.pressed { continue; }
             self.world.player.handle_keyboard_input(event.key_code, event.pressed);
+            reload_resource_packs |= event.key_code == KeyCode::F5 && event.pressed && !event.repeat;
+            if event.key_code == KeyCode::KeyE && event.pressed && !event.repeat {
+                self.is_block_picker_open = !self.is_block_picker_open;
+            }
+            if event.key_code == KeyCode::KeyG && event.pressed && !event.repeat {
+                self.world.player.set_game_mode(self.world.player.game_mode.next());
+            }
+            if event.key_code == KeyCode::KeyF && event.pressed && !event.repeat {
+                self.world.player.toggle_flying();
+            }
+            if event.key_code == KeyCode::KeyM && event.pressed && !event.repeat {
+                self.minimap.mode = self.minimap.mode.next();
+            }
+            if event.key_code == KeyCode::Equal && event.pressed {
+                self.minimap.zoom_in();
+            }
+            if event.key_code == KeyCode::Minus && event.pressed {
+                self.minimap.zoom_out();
+            }
+        }
+        if reload_resource_packs {
+            self.reload_resource_packs(game);
         }
+        // the cursor is needed to click in the block picker and the console
+        game.cursor_captured = !self.is_block_picker_open && !self.console.is_open;
 
         for event in self.mouse_input_reader.read() {
+            if self.is_block_picker_open || self.console.is_open { continue; }
+            if event.button == MouseButton::Middle && event.pressed {
+                self.world.player.pick_block(&self.world.chunk_manager);
+            }
             self.world.player.handle_mouse_input(event.button, event.pressed);
-            self.world.player.modify_block(&mut self.world.chunk_manager, BLOCK_MAP.get("torch").unwrap().clone().into());
+            self.world.player.modify_block(&mut self.world.chunk_manager);
         }
 
         for event in self.mouse_move_reader.read() {
             self.world.player.handle_mouse_movement(event.delta.map(|f| f as f32));
         }
+
+        for event in self.mouse_scroll_reader.read() {
+            self.world.player.handle_mouse_scroll(event.delta);
+        }
+
+        // a crash doesn't lose more than the last interval
+        self.autosave_interval.tick(|| {
+            if let Err(err) = self.world.player.save(PLAYER_SAVE_PATH) {
+                println!("couldn't save the player: {}", err);
+            }
+        });
+
+        if game.quit {
+            if let Err(err) = self.world.player.save(PLAYER_SAVE_PATH) {
+                println!("couldn't save the player: {}", err);
+            }
+        }
     }
 
     fn on_render(&mut self, events: &EventManager, game: &mut Game) {
         let dt = game.last_render_instant.elapsed();
-        self.world.player.update(dt.as_secs_f32());
-        let debug_gui = DebugGui::new(&self.world, dt, game.last_update_time);
-        debug_gui.show(game.egui_winit_state.egui_ctx());
+        // the player would fall through the chunks that aren't generated yet
+        if !self.world.is_player_waiting_for_spawn() {
+            self.world.player.update(dt.as_secs_f32(), &self.world.chunk_manager);
+        }
+        let shader_errors = self.shader_manager.as_ref().map(|shader_manager| shader_manager.errors()).unwrap_or_default();
+        let target = self.world.player.raycast_block(&self.world.chunk_manager);
+        let debug_gui = DebugGui::new(&self.world, target.as_ref(), dt, game.last_update_time, shader_errors);
+        let debug_gui_response = debug_gui.show(game.egui_winit_state.egui_ctx());
+        if debug_gui_response.reload_resource_packs {
+            self.reload_resource_packs(game);
+        }
+        if let Some(game_mode) = debug_gui_response.game_mode {
+            self.world.player.set_game_mode(game_mode);
+        }
+        if debug_gui_response.set_spawn {
+            if let Err(err) = self.world.set_spawn(self.world.player.feet_block_position()) {
+                println!("couldn't save the spawn: {}", err);
+            }
+        }
+        HotbarGui::new(&self.world.player.hotbar).show(game.egui_winit_state.egui_ctx(), &mut self.block_icons);
+        self.minimap.update(game.egui_winit_state.egui_ctx(), &self.world.chunk_manager.chunk_map_lock.read(), self.world.player.position.chunk.xz());
+        self.minimap.show(game.egui_winit_state.egui_ctx(), self.world.player.position, self.world.player.yaw);
+        if self.is_block_picker_open {
+            if let Some(block_name) = BlockPickerGui::show(game.egui_winit_state.egui_ctx(), &mut self.block_icons) {
+                self.world.player.hotbar.set_selected(HotbarItem::new(&block_name));
+            }
+        }
+        if self.console.is_open && ConsoleGui::show(game.egui_winit_state.egui_ctx(), &mut self.console).submitted {
+            let requests = self.console.submit(&mut self.world);
+            if requests.reload_resource_packs {
+                self.reload_resource_packs(game);
+            }
+        }
+        self.world.chunk_renderer.set_daylight(self.world.time.daylight());
         self.world.chunk_renderer.view_projection.update_from_matrix(self.world.player.build_view_projection_matrix(game.aspect_ratio));
-        self.world.chunk_renderer.render(&game.device, &game.queue, &mut self.world.chunk_manager, &mut game.render_thread);
+        self.world.chunk_renderer.render(&game.device, &game.queue, &mut self.world.chunk_manager, &mut game.render_thread, self.world.player.position);
+        self.world.block_breaking_renderer.render(&game.queue, &mut game.render_thread, &self.world.chunk_renderer, target.as_ref(), self.world.player.mining);
+        self.world.block_outline_renderer.render(&game.queue, &mut game.render_thread, &self.world.chunk_renderer, target.as_ref());
     }
 }
 
@@ -122,11 +226,81 @@ impl GameLogicLayer {
             interval_300hz: Interval::new_hz(300.0),
             interval_60hz: Interval::new_hz(60.0),
             interval_20hz: Interval::new_hz(20.0),
+            autosave_interval: Interval::new(PLAYER_AUTOSAVE_INTERVAL),
             keyboard_input_reader: event_manager.create_reader(),
             mouse_input_reader: event_manager.create_reader(),
             mouse_move_reader: event_manager.create_reader(),
+            mouse_scroll_reader: event_manager.create_reader(),
+            block_icons: BlockIcons::new(),
+            is_block_picker_open: false,
+            console: Console::new(),
+            minimap: MinimapGui::new(),
+            asset_watcher: Self::create_asset_watcher(&settings.resource_packs),
+            shader_manager: match ShaderManager::new() {
+                Ok(shader_manager) => Some(shader_manager),
+                Err(err) => {
+                    println!("couldn't watch the shaders: {}", err);
+                    None
+                }
+            },
         })
     }
+
+    fn reload_shaders(&mut self, game: &Game, changed_files: &[std::path::PathBuf]) {
+        let Some(shader_manager) = self.shader_manager.as_mut() else { return; };
+        if self.world.chunk_renderer.shader_depends_on(changed_files) {
+            shader_manager.report("chunk", self.world.chunk_renderer.reload_shaders(&game.device, &game.surface_config));
+        }
+        if self.world.block_outline_renderer.overlay.shader_depends_on(changed_files) {
+            shader_manager.report("block outline", self.world.block_outline_renderer.overlay.reload_shaders(&game.device, &game.surface_config, &self.world.chunk_renderer));
+        }
+        if self.world.block_breaking_renderer.overlay.shader_depends_on(changed_files) {
+            shader_manager.report("block breaking", self.world.block_breaking_renderer.overlay.reload_shaders(&game.device, &game.surface_config, &self.world.chunk_renderer));
+        }
+    }
+
+    fn create_asset_watcher(resource_pack_paths: &[std::path::PathBuf]) -> Option<AssetWatcher> {
+        match AssetWatcher::new(resource_pack_paths) {
+            Ok(asset_watcher) => Some(asset_watcher),
+            Err(err) => {
+                println!("couldn't watch the resource packs, they can still be reloaded with F5: {}", err);
+                None
+            }
+        }
+    }
+
+    // the pack list is read again from the settings file so that packs can be added without restarting,
+    // if anything fails the old assets are kept
+    fn reload_resource_packs(&mut self, game: &Game) {
+        let settings = match Settings::from_file(&game.settings_path) {
+            Ok(settings) => settings,
+            Err(err) => {
+                println!("couldn't read the settings, keeping the current resource packs: {}", err);
+                return;
+            }
+        };
+        // the pack list might have changed
+        self.asset_watcher = Self::create_asset_watcher(&settings.resource_packs);
+
+        let assets = ResourcePackStack::new(BASE_RESOURCE_PACK_PATH, &settings.resource_packs)
+            .and_then(|resource_packs| AssetDiagnostics::load(|diagnostics| load_assets(&resource_packs, Some(&assets()), diagnostics)));
+        let assets = match assets {
+            Ok(assets) => assets,
+            Err(err) => {
+                println!("couldn't reload the resource packs, keeping the current assets: {}", err);
+                return;
+            }
+        };
+
+        set_assets(assets);
+        self.block_icons.clear();
+        self.minimap.clear();
+        if let Err(err) = self.world.chunk_renderer.reload_assets(&game.device, &game.queue, &game.surface_config) {
+            println!("couldn't reload the chunk renderer: {}", err);
+        }
+        self.world.chunk_manager.refresh_assets();
+        println!("Reloaded resource packs");
+    }
 }
 
 #[derive(Clone)]
@@ -1,6 +1,6 @@
-use winit::{event::{DeviceEvent, WindowEvent}, keyboard::{KeyCode, PhysicalKey}};
+use winit::{event::{DeviceEvent, MouseScrollDelta, WindowEvent}, keyboard::{KeyCode, PhysicalKey}};
 
-use crate::{event::{EventManager, EventReader}, game_window::{GameWindowEvent, KeyboardInputEvent, MouseInputEvent, MouseMoveEvent}, GLOBAL_RESOURCES};
+use crate::{event::{EventManager, EventReader}, game_window::{GameWindowEvent, KeyboardInputEvent, MouseInputEvent, MouseMoveEvent, MouseScrollEvent}, GLOBAL_RESOURCES};
 
 use super::Layer;
 
@@ -32,6 +32,13 @@ impl Layer for GameWindowLayer {
                         WindowEvent::MouseInput { button, state, .. } => {
                             events.send(MouseInputEvent { button, pressed: state.is_pressed() });
                         },
+                        WindowEvent::MouseWheel { delta, .. } => {
+                            let delta = match delta {
+                                MouseScrollDelta::LineDelta(_, y) => y,
+                                MouseScrollDelta::PixelDelta(position) => position.y as f32 / 32.0,
+                            };
+                            events.send(MouseScrollEvent { delta });
+                        },
                         WindowEvent::RedrawRequested => {
                             events.send(GameWindowEvent::RedrawRequested);
                             game.is_render_frame = true;
@@ -49,6 +56,7 @@ impl Layer for GameWindowLayer {
                     match event {
                         DeviceEvent::MouseMotion { delta } => {
                             game.egui_winit_state.on_mouse_motion(delta);
+                            if !game.cursor_captured { continue; }
                             events.send(MouseMoveEvent { delta: delta.into() });
 
                             // TODO temp
@@ -66,7 +74,7 @@ impl Layer for GameWindowLayer {
     }
 
     fn on_render(&mut self, events: &EventManager, game: &mut crate::game::Game) {
-        game.game_window.window().set_cursor_visible(false);
+        game.game_window.window().set_cursor_visible(!game.cursor_captured);
         game.egui_winit_state.handle_platform_output(game.game_window.window(), game.egui_full_output.platform_output.clone());
         let raw_input = game.egui_winit_state.take_egui_input(game.game_window.window());
         game.egui_winit_state.egui_ctx().begin_frame(raw_input);
@@ -0,0 +1,106 @@
+#![feature(variant_count, trait_alias)]
+use std::sync::Arc;
+
+use arc_swap::ArcSwap;
+use block::{asset_loader::Assets, Block, BlockId};
+use cgmath::Vector3;
+use event::EventManagerBuilder;
+use global_resources::{GlobalResources, GlobalResourcesBuilder};
+use hashbrown::HashMap;
+use world::structure::Structure;
+
+pub mod game;
+mod game_window;
+mod settings;
+mod camera;
+mod global_vector;
+mod world;
+pub mod block;
+mod texture;
+mod collision;
+mod interval;
+mod render_thread;
+mod thread_work_dispatcher;
+mod gui;
+mod layer;
+mod event;
+mod typemap;
+mod keybinds;
+mod utils;
+mod shader;
+mod fog;
+mod chunk_position;
+mod global_resources;
+mod console;
+mod directory_watcher;
+
+pub use world::{map::render_regions, REGIONS_SAVE_DIRECTORY};
+
+lazy_static::lazy_static! {
+    // block ids don't change when the assets are reloaded
+    pub static ref AIR_ID: BlockId = *assets().block_map.get("air").unwrap().id();
+
+    pub static ref STRUCTURES: HashMap<String, Structure> = {
+        let mut structures = HashMap::new();
+        let oak_log: Block = assets().block_map.get("oak_log").unwrap().clone().into();
+        let oak_leaves: Block = assets().block_map.get("oak_leaves").unwrap().clone().into();
+
+        structures.insert("tree".to_string(), Structure {
+            blocks: vec![
+                (Vector3::new(0, 0, 0), oak_log.clone()),
+                (Vector3::new(0, 1, 0), oak_log.clone()),
+                (Vector3::new(0, 2, 0), oak_log.clone()),
+                (Vector3::new(0, 3, 0), oak_leaves.clone()),
+                (Vector3::new(1, 3, 0), oak_leaves.clone()),
+                (Vector3::new(0, 3, 1), oak_leaves.clone()),
+                (Vector3::new(1, 3, 1), oak_leaves.clone()),
+                (Vector3::new(-1, 3, 0), oak_leaves.clone()),
+                (Vector3::new(0, 3, -1), oak_leaves.clone()),
+                (Vector3::new(-1, 3, -1), oak_leaves.clone()),
+                (Vector3::new(1, 3, -1), oak_leaves.clone()),
+                (Vector3::new(-1, 3, 1), oak_leaves.clone()),
+                (Vector3::new(0, 4, 0), oak_leaves.clone()),
+                (Vector3::new(1, 4, 0), oak_leaves.clone()),
+                (Vector3::new(0, 4, 1), oak_leaves.clone()),
+                (Vector3::new(1, 4, 1), oak_leaves.clone()),
+                (Vector3::new(-1, 4, 0), oak_leaves.clone()),
+                (Vector3::new(0, 4, -1), oak_leaves.clone()),
+                (Vector3::new(-1, 4, -1), oak_leaves.clone()),
+                (Vector3::new(1, 4, -1), oak_leaves.clone()),
+                (Vector3::new(-1, 4, 1), oak_leaves.clone()),
+                (Vector3::new(0, 5, 0), oak_leaves.clone()),
+            ]
+        });
+
+        structures
+    };
+}
+
+static ASSETS: std::sync::OnceLock<ArcSwap<Assets>> = std::sync::OnceLock::new();
+
+// the assets loaded from the resource packs, they get replaced when the resource packs are reloaded
+pub fn assets() -> arc_swap::Guard<Arc<Assets>> {
+    ASSETS.get().expect("assets aren't loaded").load()
+}
+
+pub fn set_assets(assets: Assets) {
+    if let Err(assets) = ASSETS.set(ArcSwap::from_pointee(assets)) {
+        ASSETS.get().unwrap().store(assets.into_inner());
+    }
+}
+
+pub static GLOBAL_RESOURCES: std::sync::LazyLock<GlobalResources> = std::sync::LazyLock::new(|| 
+    GlobalResourcesBuilder::default()
+        .register_resource(
+            EventManagerBuilder::default()
+            .register_event_type::<game_window::GameWindowEvent>()
+            .register_event_type::<layer::game_logic_layer::ChunkUpdateRenderMesh>()
+            .register_event_type::<game_window::KeyboardInputEvent>()
+            .register_event_type::<game_window::MouseInputEvent>()
+            .register_event_type::<game_window::MouseMoveEvent>()
+            .register_event_type::<game_window::MouseScrollEvent>()
+            .register_event_type::<winit::event::Event<()>>()
+            .build()
+        )
+        .build()
+);
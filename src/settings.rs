@@ -6,6 +6,11 @@ pub struct Settings {
     pub fullscreen: bool,
     pub borderless: bool,
     pub render_distance: u32,
+    #[serde(default = "crate::utils::bool_true")]
+    pub chunk_fade_in: bool,
+    // applied in order on top of ./assets, later packs override earlier ones
+    #[serde(default)]
+    pub resource_packs: Vec<std::path::PathBuf>,
 }
 
 impl Default for Settings {
@@ -15,6 +20,8 @@ impl Default for Settings {
             fullscreen: false,
             borderless: false,
             render_distance: 16,
+            chunk_fade_in: true,
+            resource_packs: vec![],
         }
     }
 }
@@ -1,7 +1,13 @@
-use std::io::Read;
+use std::{collections::BTreeMap, path::{Path, PathBuf}};
+
+use crate::directory_watcher::DirectoryWatcher;
+
+pub const SHADERS_PATH: &str = "./src/shaders";
 
 pub struct Shader {
     shader_module: wgpu::ShaderModule,
+    // the shader's file and every file it includes
+    files: Vec<PathBuf>,
 }
 
 impl Shader {
@@ -11,19 +17,102 @@ impl Shader {
             source: wgpu::ShaderSource::Wgsl(source.into())
         });
 
-        Self { shader_module }
+        Self { shader_module, files: vec![] }
     }
 
     pub fn module(&self) -> &wgpu::ShaderModule {
         &self.shader_module
     }
 
+    // fails with the compilation error instead of panicking
     pub fn from_file<P: AsRef<std::path::Path>>(device: &wgpu::Device, path: P) -> anyhow::Result<Self> {
-        let mut file = std::fs::File::open(path.as_ref())?;
         let file_name = path.as_ref().file_name().map(|f| f.to_str()).flatten().unwrap_or("shader");
-        let mut source = String::new();
-        file.read_to_string(&mut source)?;
+        let mut files = vec![];
+        let source = Self::preprocess(path.as_ref(), &mut files)?;
+
+        let mut shader = capture_validation_errors(device, || Self::from_str(device, &source, file_name))
+            .map_err(|err| anyhow::anyhow!("{}: {}", path.as_ref().display(), err))?;
+        shader.files = files;
+        Ok(shader)
+    }
+
+    // replaces `#include "path"` lines with the file's source, paths are relative to the including file,
+    // every file is included once so shared code can be included from several files
+    fn preprocess(path: &Path, files: &mut Vec<PathBuf>) -> anyhow::Result<String> {
+        let path = std::fs::canonicalize(path).map_err(|err| anyhow::anyhow!("{}: {}", path.display(), err))?;
+        files.push(path.clone());
+        let source = std::fs::read_to_string(&path)?;
+
+        let mut preprocessed = String::with_capacity(source.len());
+        for (line_index, line) in source.lines().enumerate() {
+            let Some(include) = line.trim().strip_prefix("#include") else {
+                preprocessed.push_str(line);
+                preprocessed.push('\n');
+                continue;
+            };
+
+            let Some(include_path) = include.trim().strip_prefix('"').and_then(|include| include.strip_suffix('"')) else {
+                anyhow::bail!("{}:{}: expected #include \"path\"", path.display(), line_index + 1);
+            };
+            let include_path = path.parent().unwrap_or(Path::new(".")).join(include_path);
+            if std::fs::canonicalize(&include_path).is_ok_and(|include_path| files.contains(&include_path)) { continue; }
+
+            let included = Self::preprocess(&include_path, files)
+                .map_err(|err| anyhow::anyhow!("{}:{}: {}", path.display(), line_index + 1, err))?;
+            preprocessed.push_str(&included);
+        }
+
+        Ok(preprocessed)
+    }
+
+    pub fn depends_on(&self, changed_files: &[PathBuf]) -> bool {
+        changed_files.iter().any(|changed_file| self.files.contains(changed_file))
+    }
+}
 
-        Ok(Self::from_str(device, &source, file_name))
+// runs wgpu calls and returns their validation errors, otherwise they'd panic in the device's uncaptured error handler
+pub fn capture_validation_errors<T>(device: &wgpu::Device, create: impl FnOnce() -> T) -> anyhow::Result<T> {
+    device.push_error_scope(wgpu::ErrorFilter::Validation);
+    let value = create();
+    match pollster::block_on(device.pop_error_scope()) {
+        Some(err) => Err(anyhow::anyhow!("{}", err)),
+        None => Ok(value),
     }
-}
\ No newline at end of file
+}
+
+// Watches the shaders so that the renderers can rebuild their pipelines when a file changes,
+// and keeps the errors of the rebuilds that failed for the debug gui
+pub struct ShaderManager {
+    watcher: DirectoryWatcher,
+    errors: BTreeMap<String, String>,
+}
+
+impl ShaderManager {
+    pub fn new() -> anyhow::Result<Self> {
+        let watcher = DirectoryWatcher::new("shader", &[SHADERS_PATH], |path| path.extension().is_some_and(|extension| extension == "wgsl"))?;
+        Ok(Self { watcher, errors: BTreeMap::new() })
+    }
+
+    // the shader files that changed, once they stayed untouched for a moment
+    pub fn poll(&mut self) -> Vec<PathBuf> {
+        self.watcher.poll()
+    }
+
+    // remembers the error of a failed rebuild until the same pipelines are rebuilt successfully
+    pub fn report(&mut self, name: &str, result: anyhow::Result<()>) {
+        match result {
+            Ok(()) => {
+                self.errors.remove(name);
+                println!("Rebuilt {} pipelines", name);
+            },
+            Err(err) => {
+                println!("couldn't rebuild {} pipelines: {}", name, err);
+                self.errors.insert(name.to_string(), err.to_string());
+            },
+        }
+    }
+
+    pub fn errors(&self) -> Vec<String> {
+        self.errors.iter().map(|(name, err)| format!("{}: {}", name, err)).collect()
+    }
+}
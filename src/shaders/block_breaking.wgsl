@@ -0,0 +1,64 @@
+struct CameraUniform {
+    view_projection: mat4x4<f32>,
+}
+
+@group(0) @binding(0) var<uniform> camera: CameraUniform;
+
+const CRACK_STAGES: f32 = 10.0;
+// the cracks are drawn in the same resolution as the block textures
+const TEXTURE_SIZE: f32 = 16.0;
+const CRACK_COLOR: vec4f = vec4f(0.08, 0.08, 0.08, 0.75);
+
+struct VertexOutput {
+    @builtin(position) clip_position: vec4f,
+    @location(0) uv: vec2f,
+    @location(1) stage: f32,
+}
+
+@vertex
+fn vs_main(@location(0) position: vec3f, @location(1) uv: vec2f, @location(2) stage: f32) -> VertexOutput {
+    var out: VertexOutput;
+    out.clip_position = camera.view_projection * vec4f(position, 1.0);
+    out.uv = uv;
+    out.stage = stage;
+    return out;
+}
+
+fn hash(point: vec2f) -> vec2f {
+    let q = vec2f(dot(point, vec2f(127.1, 311.7)), dot(point, vec2f(269.5, 183.3)));
+    return fract(sin(q) * 43758.5453);
+}
+
+// 0 on the borders between voronoi cells, the borders are the cracks
+fn crack_distance(point: vec2f) -> f32 {
+    let cell = floor(point);
+    var nearest = 8.0;
+    var second_nearest = 8.0;
+    for (var y = -1; y <= 1; y++) {
+        for (var x = -1; x <= 1; x++) {
+            let neighbor = cell + vec2f(f32(x), f32(y));
+            let feature_distance = distance(point, neighbor + hash(neighbor));
+            if feature_distance < nearest {
+                second_nearest = nearest;
+                nearest = feature_distance;
+            } else if feature_distance < second_nearest {
+                second_nearest = feature_distance;
+            }
+        }
+    }
+    return second_nearest - nearest;
+}
+
+@fragment
+fn fs_main(in: VertexOutput) -> @location(0) vec4f {
+    let pixel = (floor(in.uv * TEXTURE_SIZE) + 0.5) / TEXTURE_SIZE;
+    let spread = (in.stage + 1.0) / CRACK_STAGES;
+    // the cracks grow out of the middle of the face and get wider as the block breaks
+    if distance(pixel, vec2f(0.5)) > spread * 0.75 + 0.05 {
+        discard;
+    }
+    if crack_distance(pixel * 3.0) > 0.08 + 0.12 * spread {
+        discard;
+    }
+    return CRACK_COLOR;
+}
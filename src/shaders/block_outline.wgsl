@@ -0,0 +1,17 @@
+struct CameraUniform {
+    view_projection: mat4x4<f32>,
+}
+
+@group(0) @binding(0) var<uniform> camera: CameraUniform;
+
+const OUTLINE_COLOR: vec4f = vec4f(0.0, 0.0, 0.0, 0.6);
+
+@vertex
+fn vs_main(@location(0) position: vec3f) -> @builtin(position) vec4f {
+    return camera.view_projection * vec4f(position, 1.0);
+}
+
+@fragment
+fn fs_main() -> @location(0) vec4f {
+    return OUTLINE_COLOR;
+}
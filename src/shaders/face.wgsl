@@ -0,0 +1,42 @@
+// unpacks the faces written by the chunk part mesher, see FacePacked
+struct LightLevel {
+    block: u32,
+    sky: u32
+}
+
+struct Face {
+    lighting: array<LightLevel, 4>,
+    block_position: array<u32, 3>,
+    texture_index: u32,
+    quad_index: u32,
+}
+
+fn construct_light_level(raw_data: u32) -> LightLevel {
+    var out: LightLevel;
+    out.block = raw_data & 15u;
+    out.sky = raw_data >> 4u & 15u;
+
+    return out;
+}
+
+fn construct_face(face_raw_data: vec4u) -> Face {
+    var face: Face;
+    var lighting: array<LightLevel, 4>;
+
+    lighting[0] = construct_light_level(face_raw_data.x);
+    lighting[1] = construct_light_level(face_raw_data.x >> 8u);
+    lighting[2] = construct_light_level(face_raw_data.x >> 16u);
+    lighting[3] = construct_light_level(face_raw_data.x >> 24u);
+    face.lighting = lighting;
+
+    var block_position: array<u32, 3>;
+    block_position[0] = (face_raw_data.y) & 31u;
+    block_position[1] = (face_raw_data.y >> 5u & 31u);
+    block_position[2] = (face_raw_data.y >> 10u & 31u);
+    face.block_position = block_position;
+
+    face.texture_index = face_raw_data.y >> 16u & 65535u;
+    face.quad_index = (face_raw_data.z & 65535u);
+    
+    return(face);
+}
@@ -4,7 +4,8 @@ use std::{io::Read, sync::Arc};
 pub struct Texture {
     texture: Arc<wgpu::Texture>,
     view: Arc<wgpu::TextureView>,
-    sampler: Arc<wgpu::Sampler>
+    sampler: Arc<wgpu::Sampler>,
+    view_dimension: wgpu::TextureViewDimension,
 }
 
 
@@ -80,6 +81,7 @@ impl Texture {
             texture: Arc::new(texture),
             view: Arc::new(view),
             sampler: Arc::new(sampler),
+            view_dimension: wgpu::TextureViewDimension::D2,
         }
     }
 
@@ -92,6 +94,80 @@ impl Texture {
         Self::from_bytes(device, queue, &bytes, file_name)
     }
 
+    // every image becomes a layer of the array, all images need to have the same dimensions
+    pub fn from_image_array(device: &wgpu::Device, queue: &wgpu::Queue, images: &[image::RgbaImage], label: Option<&str>) -> Self {
+        let (width, height) = images[0].dimensions();
+        let mip_level_count = width.max(height).ilog2() + 1;
+
+        let texture = device.create_texture(&wgpu::TextureDescriptor {
+            label,
+            size: wgpu::Extent3d {
+                width,
+                height,
+                depth_or_array_layers: images.len() as u32
+            },
+            mip_level_count,
+            dimension: wgpu::TextureDimension::D2,
+            format: wgpu::TextureFormat::Rgba8UnormSrgb,
+            usage: wgpu::TextureUsages::TEXTURE_BINDING | wgpu::TextureUsages::COPY_DST,
+            view_formats: &[],
+            sample_count: 1
+        });
+
+        for (layer, image) in images.iter().enumerate() {
+            for mip_level in 0..mip_level_count {
+                let mip_width = (width >> mip_level).max(1);
+                let mip_height = (height >> mip_level).max(1);
+                let mip_image = if mip_level == 0 {
+                    image.clone()
+                } else {
+                    image::imageops::resize(image, mip_width, mip_height, image::imageops::FilterType::Triangle)
+                };
+
+                queue.write_texture(
+                    wgpu::ImageCopyTexture {
+                        texture: &texture,
+                        mip_level,
+                        origin: wgpu::Origin3d { x: 0, y: 0, z: layer as u32 },
+                        aspect: wgpu::TextureAspect::All
+                    },
+                    &mip_image,
+                    wgpu::ImageDataLayout {
+                        offset: 0,
+                        bytes_per_row: Some(4 * mip_width),
+                        rows_per_image: Some(mip_height),
+                    },
+                    wgpu::Extent3d {
+                        width: mip_width,
+                        height: mip_height,
+                        depth_or_array_layers: 1
+                    }
+                );
+            }
+        }
+
+        let view = texture.create_view(&wgpu::TextureViewDescriptor {
+            dimension: Some(wgpu::TextureViewDimension::D2Array),
+            ..Default::default()
+        });
+        let sampler = device.create_sampler(&wgpu::SamplerDescriptor {
+            address_mode_u: wgpu::AddressMode::Repeat,
+            address_mode_v: wgpu::AddressMode::Repeat,
+            address_mode_w: wgpu::AddressMode::Repeat,
+            mag_filter: wgpu::FilterMode::Nearest,
+            min_filter: wgpu::FilterMode::Nearest,
+            mipmap_filter: wgpu::FilterMode::Linear,
+            ..Default::default()
+        });
+
+        Self {
+            texture: Arc::new(texture),
+            view: Arc::new(view),
+            sampler: Arc::new(sampler),
+            view_dimension: wgpu::TextureViewDimension::D2Array,
+        }
+    }
+
     pub const DEPTH_FORMAT: wgpu::TextureFormat = wgpu::TextureFormat::Depth32Float;
 
     pub fn create_depth_texture(device: &wgpu::Device, config: &wgpu::SurfaceConfiguration, label: &str) -> Self {
@@ -133,6 +209,7 @@ impl Texture {
             texture: Arc::new(texture),
             view: Arc::new(view),
             sampler: Arc::new(sampler),
+            view_dimension: wgpu::TextureViewDimension::D2,
         }
     }
 
@@ -144,7 +221,7 @@ impl Texture {
                 ty: wgpu::BindingType::Texture {
                     multisampled: self.texture.sample_count() > 1,
                     sample_type: wgpu::TextureSampleType::Float { filterable: true }, // TODO might be something else across different textures
-                    view_dimension: wgpu::TextureViewDimension::D2,
+                    view_dimension: self.view_dimension,
                 },
                 visibility: wgpu::ShaderStages::VERTEX_FRAGMENT,
             },
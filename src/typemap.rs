@@ -15,12 +15,12 @@ impl TypeMap where {
 
     #[inline]
     pub fn get<K: 'static>(&self) -> Option<&K> {
-        self.inner.get(&TypeId::of::<K>()).map(|f| unsafe { f.downcast_ref_unchecked() })
+        self.inner.get(&TypeId::of::<K>()).and_then(|f| f.downcast_ref())
     }
 
     #[inline]
     pub fn get_mut<K: 'static>(&mut self) -> Option<&mut K> {
-        self.inner.get_mut(&TypeId::of::<K>()).map(|f| unsafe { f.downcast_mut_unchecked() })
+        self.inner.get_mut(&TypeId::of::<K>()).and_then(|f| f.downcast_mut())
     }
 
     #[inline]
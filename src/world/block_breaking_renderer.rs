@@ -0,0 +1,83 @@
+use cgmath::Vector3;
+
+use crate::{global_vector::GlobalVecF, render_thread::RenderThread};
+
+use super::{block_overlay_renderer::{BlockOverlayDescriptor, BlockOverlayPipeline, OVERLAY_OFFSET}, chunk::chunk_renderer::ChunkRenderer, player::MiningProgress, raycast::BlockRaycastResult};
+
+const MAX_CRACKED_HITBOXES: usize = 64;
+const VERTICES_PER_HITBOX: usize = 36;
+const VERTEX_ATTRIBUTES: [wgpu::VertexAttribute; 3] = wgpu::vertex_attr_array![0 => Float32x3, 1 => Float32x2, 2 => Float32];
+
+#[repr(C)]
+#[derive(Clone, Copy, bytemuck::Zeroable, bytemuck::Pod)]
+struct CrackVertex {
+    position: [f32; 3],
+    uv: [f32; 2],
+    stage: f32,
+}
+
+// Draws the cracks of the block that's being mined over its hitboxes
+pub struct BlockBreakingRenderer {
+    pub overlay: BlockOverlayPipeline,
+}
+
+impl BlockBreakingRenderer {
+    pub fn new(device: &wgpu::Device, surface_config: &wgpu::SurfaceConfiguration, chunk_renderer: &ChunkRenderer) -> anyhow::Result<Self> {
+        let overlay = BlockOverlayPipeline::new(device, surface_config, chunk_renderer, BlockOverlayDescriptor {
+            label: "BlockBreakingRenderer",
+            shader_path: "./src/shaders/block_breaking.wgsl",
+            vertex_size: std::mem::size_of::<CrackVertex>(),
+            vertex_attributes: &VERTEX_ATTRIBUTES,
+            topology: wgpu::PrimitiveTopology::TriangleList,
+            max_vertices: MAX_CRACKED_HITBOXES * VERTICES_PER_HITBOX,
+        })?;
+
+        Ok(Self { overlay })
+    }
+
+    // two triangles for every face of the hitbox, the uv is the position on the face inside the block so the cracks line up across hitboxes
+    fn hitbox_faces(start: Vector3<f32>, end: Vector3<f32>, block_origin: Vector3<f32>, stage: f32) -> [CrackVertex; VERTICES_PER_HITBOX] {
+        let start = start.map(|f| f - OVERLAY_OFFSET);
+        let end = end.map(|f| f + OVERLAY_OFFSET);
+        let corner = |x: bool, y: bool, z: bool| Vector3::new(
+            if x { end.x } else { start.x },
+            if y { end.y } else { start.y },
+            if z { end.z } else { start.z },
+        );
+        // the face's corners and the axes its uv runs along
+        let faces = [
+            ([corner(true, false, false), corner(true, false, true), corner(true, true, false), corner(true, true, true)], (2, 1)),
+            ([corner(false, false, false), corner(false, false, true), corner(false, true, false), corner(false, true, true)], (2, 1)),
+            ([corner(false, true, false), corner(true, true, false), corner(false, true, true), corner(true, true, true)], (0, 2)),
+            ([corner(false, false, false), corner(true, false, false), corner(false, false, true), corner(true, false, true)], (0, 2)),
+            ([corner(false, false, true), corner(true, false, true), corner(false, true, true), corner(true, true, true)], (0, 1)),
+            ([corner(false, false, false), corner(true, false, false), corner(false, true, false), corner(true, true, false)], (0, 1)),
+        ];
+
+        let mut vertices = [CrackVertex { position: [0.0; 3], uv: [0.0; 2], stage }; VERTICES_PER_HITBOX];
+        for (i, (corners, (u_axis, v_axis))) in faces.into_iter().enumerate() {
+            let vertex = |corner: Vector3<f32>| {
+                let local = corner - block_origin;
+                CrackVertex { position: corner.into(), uv: [local[u_axis], 1.0 - local[v_axis]], stage }
+            };
+            for (j, corner_index) in [0, 1, 2, 2, 1, 3].into_iter().enumerate() {
+                vertices[i * 6 + j] = vertex(corners[corner_index]);
+            }
+        }
+        vertices
+    }
+
+    pub fn render(&self, queue: &wgpu::Queue, render_thread: &mut RenderThread, chunk_renderer: &ChunkRenderer, target: Option<&BlockRaycastResult>, mining: Option<MiningProgress>) {
+        let (Some(target), Some(mining)) = (target, mining) else { return; };
+        if target.block_position != mining.position { return; }
+
+        let block_origin: GlobalVecF = target.block_position.into();
+        let block_origin: Vector3<f32> = block_origin.into();
+        let stage = mining.crack_stage() as f32;
+        let vertices = target.hitboxes().iter()
+            .take(MAX_CRACKED_HITBOXES)
+            .flat_map(|hitbox| Self::hitbox_faces(hitbox.start.into(), hitbox.end.into(), block_origin, stage))
+            .collect::<Vec<CrackVertex>>();
+        self.overlay.render(queue, render_thread, chunk_renderer, &vertices);
+    }
+}
@@ -0,0 +1,67 @@
+use cgmath::Vector3;
+
+use crate::render_thread::RenderThread;
+
+use super::{block_overlay_renderer::{BlockOverlayDescriptor, BlockOverlayPipeline, OVERLAY_OFFSET}, chunk::chunk_renderer::ChunkRenderer, raycast::BlockRaycastResult};
+
+const MAX_OUTLINED_HITBOXES: usize = 64;
+const VERTICES_PER_HITBOX: usize = 24;
+const VERTEX_ATTRIBUTES: [wgpu::VertexAttribute; 1] = wgpu::vertex_attr_array![0 => Float32x3];
+
+// Draws a wireframe of the targeted block's hitboxes on top of the chunks
+pub struct BlockOutlineRenderer {
+    pub overlay: BlockOverlayPipeline,
+}
+
+impl BlockOutlineRenderer {
+    pub fn new(device: &wgpu::Device, surface_config: &wgpu::SurfaceConfiguration, chunk_renderer: &ChunkRenderer) -> anyhow::Result<Self> {
+        let overlay = BlockOverlayPipeline::new(device, surface_config, chunk_renderer, BlockOverlayDescriptor {
+            label: "BlockOutlineRenderer",
+            shader_path: "./src/shaders/block_outline.wgsl",
+            vertex_size: std::mem::size_of::<[f32; 3]>(),
+            vertex_attributes: &VERTEX_ATTRIBUTES,
+            topology: wgpu::PrimitiveTopology::LineList,
+            max_vertices: MAX_OUTLINED_HITBOXES * VERTICES_PER_HITBOX,
+        })?;
+
+        Ok(Self { overlay })
+    }
+
+    fn hitbox_edges(start: Vector3<f32>, end: Vector3<f32>) -> [[f32; 3]; VERTICES_PER_HITBOX] {
+        let start = start.map(|f| f - OVERLAY_OFFSET);
+        let end = end.map(|f| f + OVERLAY_OFFSET);
+        let corner = |x: bool, y: bool, z: bool| [
+            if x { end.x } else { start.x },
+            if y { end.y } else { start.y },
+            if z { end.z } else { start.z },
+        ];
+
+        [
+            // bottom
+            corner(false, false, false), corner(true, false, false),
+            corner(true, false, false), corner(true, false, true),
+            corner(true, false, true), corner(false, false, true),
+            corner(false, false, true), corner(false, false, false),
+            // top
+            corner(false, true, false), corner(true, true, false),
+            corner(true, true, false), corner(true, true, true),
+            corner(true, true, true), corner(false, true, true),
+            corner(false, true, true), corner(false, true, false),
+            // sides
+            corner(false, false, false), corner(false, true, false),
+            corner(true, false, false), corner(true, true, false),
+            corner(true, false, true), corner(true, true, true),
+            corner(false, false, true), corner(false, true, true),
+        ]
+    }
+
+    pub fn render(&self, queue: &wgpu::Queue, render_thread: &mut RenderThread, chunk_renderer: &ChunkRenderer, target: Option<&BlockRaycastResult>) {
+        let Some(target) = target else { return; };
+
+        let vertices = target.hitboxes().iter()
+            .take(MAX_OUTLINED_HITBOXES)
+            .flat_map(|hitbox| Self::hitbox_edges(hitbox.start.into(), hitbox.end.into()))
+            .collect::<Vec<[f32; 3]>>();
+        self.overlay.render(queue, render_thread, chunk_renderer, &vertices);
+    }
+}
@@ -0,0 +1,159 @@
+use std::sync::Arc;
+
+use crate::{render_thread::RenderThread, shader::{capture_validation_errors, Shader}, texture::Texture, utils::render_pipeline_bundle::RenderPipelineBundle};
+
+use super::chunk::chunk_renderer::ChunkRenderer;
+
+// pushes the overlays slightly out of the block's faces so they don't z-fight with them
+pub const OVERLAY_OFFSET: f32 = 0.002;
+
+// What an overlay draws and how its vertices look
+#[derive(Clone, Copy)]
+pub struct BlockOverlayDescriptor {
+    // the wgpu objects' labels start with it
+    pub label: &'static str,
+    pub shader_path: &'static str,
+    pub vertex_size: usize,
+    pub vertex_attributes: &'static [wgpu::VertexAttribute],
+    pub topology: wgpu::PrimitiveTopology,
+    pub max_vertices: usize,
+}
+
+// A pipeline that draws vertices over the chunks with their depth, e.g. the targeted block's outline or cracks,
+// its shader is watched and reloaded like the chunks' shader
+pub struct BlockOverlayPipeline {
+    descriptor: BlockOverlayDescriptor,
+    shader: Shader,
+    render_pipeline_bundle: RenderPipelineBundle,
+    vertex_buffer: Arc<wgpu::Buffer>,
+}
+
+impl BlockOverlayPipeline {
+    pub fn new(device: &wgpu::Device, surface_config: &wgpu::SurfaceConfiguration, chunk_renderer: &ChunkRenderer, descriptor: BlockOverlayDescriptor) -> anyhow::Result<Self> {
+        let vertex_buffer = Arc::new(device.create_buffer(&wgpu::BufferDescriptor {
+            label: Some(&format!("{}_vertex_buffer", descriptor.label)),
+            mapped_at_creation: false,
+            size: (descriptor.max_vertices * descriptor.vertex_size) as u64,
+            usage: wgpu::BufferUsages::VERTEX | wgpu::BufferUsages::COPY_DST,
+        }));
+
+        let shader = Shader::from_file(device, descriptor.shader_path)?;
+        let render_pipeline_bundle = Self::create_render_pipeline_bundle(device, surface_config, &shader, chunk_renderer, &descriptor);
+
+        Ok(Self { descriptor, shader, render_pipeline_bundle, vertex_buffer })
+    }
+
+    pub fn shader_depends_on(&self, changed_files: &[std::path::PathBuf]) -> bool {
+        self.shader.depends_on(changed_files)
+    }
+
+    // rebuilds the pipeline from the shader's current source, the old one is kept if it doesn't compile
+    pub fn reload_shaders(&mut self, device: &wgpu::Device, surface_config: &wgpu::SurfaceConfiguration, chunk_renderer: &ChunkRenderer) -> anyhow::Result<()> {
+        let shader = Shader::from_file(device, self.descriptor.shader_path)?;
+        let render_pipeline_bundle = capture_validation_errors(device, || Self::create_render_pipeline_bundle(device, surface_config, &shader, chunk_renderer, &self.descriptor))?;
+
+        self.shader = shader;
+        self.render_pipeline_bundle = render_pipeline_bundle;
+        Ok(())
+    }
+
+    fn create_render_pipeline_bundle(device: &wgpu::Device, surface_config: &wgpu::SurfaceConfiguration, shader: &Shader, chunk_renderer: &ChunkRenderer, descriptor: &BlockOverlayDescriptor) -> RenderPipelineBundle {
+        let render_pipeline_layout = device.create_pipeline_layout(&wgpu::PipelineLayoutDescriptor {
+            label: Some(&format!("{}_render_pipeline_layout", descriptor.label)),
+            push_constant_ranges: &[],
+            bind_group_layouts: &[chunk_renderer.view_projection_bind_group_bundle().layout()],
+        });
+
+        let render_pipeline = device.create_render_pipeline(&wgpu::RenderPipelineDescriptor {
+            label: Some(&format!("{}_render_pipeline", descriptor.label)),
+            depth_stencil: Some(wgpu::DepthStencilState {
+                bias: wgpu::DepthBiasState::default(),
+                depth_compare: wgpu::CompareFunction::LessEqual,
+                depth_write_enabled: false,
+                format: Texture::DEPTH_FORMAT,
+                stencil: wgpu::StencilState::default()
+            }),
+            vertex: wgpu::VertexState {
+                buffers: &[wgpu::VertexBufferLayout {
+                    array_stride: descriptor.vertex_size as u64,
+                    step_mode: wgpu::VertexStepMode::Vertex,
+                    attributes: descriptor.vertex_attributes,
+                }],
+                entry_point: "vs_main",
+                module: shader.module(),
+                compilation_options: wgpu::PipelineCompilationOptions::default(),
+            },
+            fragment: Some(wgpu::FragmentState {
+                entry_point: "fs_main",
+                module: shader.module(),
+                targets: &[Some(
+                    wgpu::ColorTargetState {
+                        format: surface_config.format,
+                        blend: Some(wgpu::BlendState::ALPHA_BLENDING),
+                        write_mask: wgpu::ColorWrites::all()
+                    }
+                )],
+                compilation_options: wgpu::PipelineCompilationOptions::default(),
+            }),
+            layout: Some(&render_pipeline_layout),
+            multisample: wgpu::MultisampleState {
+                alpha_to_coverage_enabled: false,
+                count: 1,
+                mask: !0
+            },
+            multiview: None,
+            primitive: wgpu::PrimitiveState {
+                conservative: false,
+                cull_mode: None,
+                front_face: wgpu::FrontFace::Ccw,
+                polygon_mode: wgpu::PolygonMode::Fill,
+                strip_index_format: None,
+                topology: descriptor.topology,
+                unclipped_depth: false
+            },
+        });
+
+        RenderPipelineBundle::new(render_pipeline, render_pipeline_layout)
+    }
+
+    // the vertices past max_vertices are left out
+    pub fn render<V: bytemuck::Pod>(&self, queue: &wgpu::Queue, render_thread: &mut RenderThread, chunk_renderer: &ChunkRenderer, vertices: &[V]) {
+        let vertices = &vertices[..vertices.len().min(self.descriptor.max_vertices)];
+        if vertices.is_empty() { return; }
+
+        queue.write_buffer(&self.vertex_buffer, 0, bytemuck::cast_slice(vertices));
+
+        let vertices_num = vertices.len() as u32;
+        let label = format!("{}_render_pass", self.descriptor.label);
+        let render_pipeline_bundle = self.render_pipeline_bundle.clone();
+        let vertex_buffer = self.vertex_buffer.clone();
+        let view_projection_bind_group_bundle = chunk_renderer.view_projection_bind_group_bundle().clone();
+        let depth_texture = chunk_renderer.depth_texture().clone();
+
+        render_thread.push_render(move |_, encoder, view| {
+            let mut render_pass = encoder.begin_render_pass(&wgpu::RenderPassDescriptor {
+                label: Some(&label),
+                color_attachments: &[Some(wgpu::RenderPassColorAttachment {
+                    view,
+                    resolve_target: None,
+                    ops: wgpu::Operations {
+                        load: wgpu::LoadOp::Load,
+                        store: wgpu::StoreOp::Store
+                    }
+                })],
+                depth_stencil_attachment: Some(wgpu::RenderPassDepthStencilAttachment {
+                    depth_ops: Some(wgpu::Operations { load: wgpu::LoadOp::Load, store: wgpu::StoreOp::Store }),
+                    stencil_ops: None,
+                    view: depth_texture.view()
+                }),
+                timestamp_writes: None,
+                occlusion_query_set: None
+            });
+
+            render_pass.set_pipeline(render_pipeline_bundle.render_pipeline());
+            render_pass.set_bind_group(0, view_projection_bind_group_bundle.bind_group(), &[]);
+            render_pass.set_vertex_buffer(0, vertex_buffer.slice(..));
+            render_pass.draw(0..vertices_num, 0..1);
+        });
+    }
+}
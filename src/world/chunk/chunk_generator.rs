@@ -4,7 +4,7 @@ use cgmath::{Vector2, Vector3};
 use parking_lot::RwLock;
 use hashbrown::HashSet;
 
-use crate::{block::Block, chunk_position::ChunkPosition, thread_work_dispatcher::ThreadWorkDispatcher, world::{CHUNK_HEIGHT, PARTS_PER_CHUNK}, BLOCK_MAP, STRUCTURES};
+use crate::{block::Block, chunk_position::ChunkPosition, thread_work_dispatcher::ThreadWorkDispatcher, world::{CHUNK_HEIGHT, PARTS_PER_CHUNK}, STRUCTURES, assets};
 
 use super::{chunk_map::{ChunkMap, ChunkMapLock}, chunk_mesh_map::ChunkMeshMap, chunk_part::{chunk_part_mesher::ChunkPartMesher, chunk_part_position::ChunkPartPosition, expanded_chunk_part::ExpandedChunkPart, CHUNK_SIZE, CHUNK_SIZE_U32}, chunks3x3::Chunks3x3, dynamic_chunk_mesh::DynamicChunkMesh, Chunk, ChunkRef};
 
@@ -12,6 +12,9 @@ lazy_static::lazy_static! {
     static ref DBG: Arc<Mutex<(usize, std::time::Duration, std::time::Duration, std::time::Duration)>> = Arc::new(Mutex::new((0, std::time::Duration::ZERO, std::time::Duration::ZERO, std::time::Duration::MAX)));
 }
 
+// the terrain's noise is seeded with it and the decorations with the next number
+pub const WORLD_SEED: i32 = 1;
+
 #[derive(Debug)]
 pub enum ChunkGeneratorInput {
     Chunk(ChunkRef),
@@ -92,9 +95,9 @@ impl ChunkGenerator {
             )
             .with_octaves(4)
             .with_freq(0.05)
-            .with_seed(1)
+            .with_seed(WORLD_SEED)
             .generate().0;
-            let stone_id = part.block_pallet.insert_block(BLOCK_MAP.get("stone").unwrap().clone().into());
+            let stone_id = part.block_pallet.insert_block(assets().block_map.get("stone").unwrap().clone().into());
             for y in 0..CHUNK_SIZE_U32 {
                 for z in 0..CHUNK_SIZE_U32 {
                     for x in 0..CHUNK_SIZE_U32 {
@@ -120,7 +123,7 @@ impl ChunkGenerator {
 
     fn terrain(chunks3x3: &mut Chunks3x3) {
         let center_chunk = chunks3x3.get_chunk_mut(Vector2::new(0, 0)).unwrap();
-        let grass: Block = BLOCK_MAP.get("grass").unwrap().clone().into();
+        let grass: Block = assets().block_map.get("grass").unwrap().clone().into();
         for y in 0..CHUNK_HEIGHT {
             for z in 0..CHUNK_SIZE {
                 for x in 0..CHUNK_SIZE {
@@ -158,7 +161,7 @@ impl ChunkGenerator {
         )
         .with_octaves(2)
         .with_freq(10.5)
-        .with_seed(2)
+        .with_seed(WORLD_SEED + 1)
         .generate().0;
         for z in 0..CHUNK_SIZE {
             for x in 0..CHUNK_SIZE {
@@ -1,9 +1,11 @@
-use cgmath::Vector2;
-use hashbrown::HashSet;
+use std::collections::VecDeque;
 
-use crate::{global_vector::GlobalVecU, world::PARTS_PER_CHUNK};
+use cgmath::{Vector2, Vector3};
+use hashbrown::{HashMap, HashSet};
 
-use super::{chunk_generator::{ChunkGenerator, ChunkGeneratorOutput, GenerationStage}, chunk_map::ChunkMapLock, chunk_mesh_map::ChunkMeshMap, chunk_part::{chunk_part_mesher::ChunkPartMesher, expanded_chunk_part::ExpandedChunkPart}, dynamic_chunk_mesh::DynamicChunkMesh, Chunk};
+use crate::{block::{Block, FaceDirection}, global_vector::{GlobalVecF, GlobalVecU}, world::{world_edit::edit_batch::{part_block_position, EditBatch}, CHUNK_HEIGHT, PARTS_PER_CHUNK}};
+
+use super::{chunk_generator::{ChunkGenerator, ChunkGeneratorOutput, GenerationStage}, chunk_map::ChunkMapLock, chunk_mesh_map::ChunkMeshMap, chunk_part::{chunk_part_mesher::ChunkPartMesher, expanded_chunk_part::ExpandedChunkPart, CHUNK_SIZE_I32}, chunks3x3::Chunks3x3, dynamic_chunk_mesh::DynamicChunkMesh, Chunk};
 use std::sync::Arc;
 
 pub struct ChunkManager {
@@ -33,6 +35,116 @@ impl ChunkManager {
         self.render_radius = value;
     }
 
+    // sets the block and queues the light and mesh updates around it, returns false when the block isn't loaded
+    pub fn set_block(&mut self, position: GlobalVecU, block: Block) -> bool {
+        let mut chunk_map = self.chunk_map_lock.write();
+        if chunk_map.get_block(position).is_none() { return false; }
+        chunk_map.set_block(position, block);
+        self.changed_blocks.push(position);
+        true
+    }
+
+    // spreads the sky light and the light of the emitting blocks through the chunk again, light that's already there is kept,
+    // returns false when the chunk or one of its neighbors isn't loaded
+    pub fn relight_chunk(&mut self, chunk_position: Vector2<i32>) -> bool {
+        let mut chunk_map = self.chunk_map_lock.write();
+        let Some(mut chunks3x3) = Chunks3x3::new(&chunk_map, chunk_position) else { return false; };
+        chunks3x3.propagate_sky_light();
+        for y in 0..CHUNK_HEIGHT as i32 {
+            for z in 0..CHUNK_SIZE_I32 {
+                for x in 0..CHUNK_SIZE_I32 {
+                    let position = Vector3::new(x, y, z);
+                    let Some(emitted_light) = chunks3x3.get_block(position).map(|block| block.properties().emitted_light) else { continue; };
+                    if emitted_light == 0 { continue; }
+                    if chunks3x3.get_block_light_level(position).is_some_and(|light_level| light_level < emitted_light) {
+                        chunks3x3.set_block_light_level(position, emitted_light);
+                    }
+                    chunks3x3.propagate_block_light_at(position);
+                }
+            }
+        }
+        chunks3x3.return_to_chunk_map(&mut chunk_map);
+
+        // the light can spread into the neighbors
+        for z in -1..=1 {
+            for x in -1..=1 {
+                let Some(mesh) = self.chunk_mesh_map.get_mut(chunk_position + Vector2::new(x, z)) else { continue; };
+                mesh.parts_need_meshing = [true; PARTS_PER_CHUNK];
+            }
+        }
+        true
+    }
+
+    // writes the batch one chunk part at a time through the part's pallet, then spreads the light around the blocks that changed
+    // how light passes with one 3x3 of chunks per touched chunk and remeshes the touched parts with the parts around them.
+    // blocks in chunks that aren't fully generated are left alone, returns the batch that undoes the changes
+    pub fn apply_edit_batch(&mut self, batch: &EditBatch) -> EditBatch {
+        let mut undo_batch = EditBatch::new();
+        let mut touched_parts = vec![];
+        let mut relight_positions: HashMap<Vector2<i32>, Vec<Vector3<i32>>> = HashMap::new();
+        let mut chunk_map = self.chunk_map_lock.write();
+
+        for (chunk_part_position, changes) in batch.parts() {
+            let chunk_position = chunk_part_position.xz();
+            if !chunk_map.borrow_chunk(&chunk_position).is_some_and(|chunk| chunk.generation_stage == GenerationStage::LAST_GENERATION_STAGE) { continue; }
+            let chunk = chunk_map.borrow_mut_chunk(&chunk_position).unwrap();
+            let chunk_part_index = chunk_part_position.y as usize;
+
+            let mut changed = vec![];
+            for (local_position, block_index) in changes.iter().copied() {
+                let old_block = chunk.parts[chunk_part_index].get_block(local_position);
+                let block = &batch.blocks()[block_index];
+                if old_block == block { continue; }
+
+                if !old_block.properties().lights_like(&block.properties()) {
+                    let mut position = local_position.position().map(|f| f as i32);
+                    position.y += chunk_part_position.y * CHUNK_SIZE_I32;
+                    relight_positions.entry(chunk_position).or_default().push(position);
+                }
+                undo_batch.set(part_block_position(chunk_part_position, local_position), old_block);
+                changed.push((local_position, block_index));
+            }
+            if changed.is_empty() { continue; }
+
+            chunk.set_blocks_in_part(chunk_part_index, batch.blocks(), &changed);
+            touched_parts.push(chunk_part_position);
+        }
+
+        for (chunk_position, positions) in relight_positions {
+            let Some(mut chunks3x3) = Chunks3x3::new(&chunk_map, chunk_position) else { continue; };
+            chunks3x3.update_block_light_levels_at(&positions);
+            chunks3x3.update_sky_light_levels_at(&positions);
+            chunks3x3.return_to_chunk_map(&mut chunk_map);
+        }
+        drop(chunk_map);
+
+        for chunk_part_position in touched_parts {
+            for z in -1..=1 {
+                for x in -1..=1 {
+                    let Some(mesh) = self.chunk_mesh_map.get_mut(chunk_part_position.xz() + Vector2::new(x, z)) else { continue; };
+                    for chunk_part_index in chunk_part_position.y - 1..=chunk_part_position.y + 1 {
+                        if !(0..PARTS_PER_CHUNK as i32).contains(&chunk_part_index) { continue; }
+                        mesh.parts_need_meshing[chunk_part_index as usize] = true;
+                    }
+                }
+            }
+        }
+        undo_batch
+    }
+
+    // the blocks' properties and the meshes depend on the assets, so they're refreshed after the resource packs are reloaded
+    pub fn refresh_assets(&mut self) {
+        for chunk_ref in self.chunk_map_lock.write().iter_mut_chunks() {
+            for part in chunk_ref.make_mut().parts.iter_mut() {
+                part.block_pallet.refresh_properties();
+            }
+        }
+
+        for mesh in self.chunk_mesh_map.values_mut() {
+            mesh.parts_need_meshing = [true; PARTS_PER_CHUNK];
+        }
+    }
+
     pub fn collect_meshing_outputs(&mut self, device: &wgpu::Device, queue: &wgpu::Queue) {
         self.chunk_generator.collect_meshing_outputs(device, queue, &mut self.chunk_mesh_map);
     }
@@ -46,7 +158,72 @@ impl ChunkManager {
         }
         meshes.into_boxed_slice()
     }
+
+    // Walks the chunk parts outwards from the camera, only passing through faces that
+    // the previous part connects to the face it was entered from, never heading back towards the camera
+    pub fn get_visible_meshes(&self, camera_chunk_part_position: Vector3<i32>) -> Box<[(Vector2<i32>, DynamicChunkMesh, [bool; PARTS_PER_CHUNK])]> {
+        if !(0..PARTS_PER_CHUNK as i32).contains(&camera_chunk_part_position.y) {
+            return self.chunk_mesh_map.iter()
+                .filter(|(_, mesh)| mesh.parts_meshed.iter().all(|p| *p))
+                .map(|(chunk_position, mesh)| (Vector2::from(*chunk_position), mesh.clone(), [true; PARTS_PER_CHUNK]))
+                .collect();
+        }
+
+        let camera_chunk_position = camera_chunk_part_position.xz();
+        let mut visible_parts: HashMap<Vector2<i32>, [bool; PARTS_PER_CHUNK]> = HashMap::new();
+        let mut visited = HashSet::new();
+        let mut queue: VecDeque<(Vector3<i32>, Option<FaceDirection>, u8)> = VecDeque::new();
+        visited.insert(camera_chunk_part_position);
+        queue.push_back((camera_chunk_part_position, None, 0));
+
+        while let Some((position, entered_from, traversed_directions)) = queue.pop_front() {
+            let chunk_position = position.xz();
+            let mesh = self.chunk_mesh_map.get(chunk_position);
+            visible_parts.entry(chunk_position).or_insert([false; PARTS_PER_CHUNK])[position.y as usize] = true;
+
+            // parts that aren't meshed yet don't block the view
+            let visibility = mesh.filter(|mesh| mesh.parts_meshed[position.y as usize]).map(|mesh| mesh.part_visibility(position.y as usize)).unwrap_or_default();
+            for face_direction in FaceDirection::ALL {
+                if traversed_directions & (1 << face_direction.opposite() as u8) != 0 { continue; }
+                if let Some(entered_from) = entered_from {
+                    if !visibility.is_connected(entered_from, face_direction) { continue; }
+                }
+
+                let adjacent_position = position + face_direction.normal_i32();
+                if !(0..PARTS_PER_CHUNK as i32).contains(&adjacent_position.y) { continue; }
+                let offset = adjacent_position.xz() - camera_chunk_position;
+                if offset.x.unsigned_abs() > self.render_radius || offset.y.unsigned_abs() > self.render_radius { continue; }
+                if !visited.insert(adjacent_position) { continue; }
+
+                queue.push_back((adjacent_position, Some(face_direction.opposite()), traversed_directions | (1 << face_direction as u8)));
+            }
+        }
+
+        let mut meshes = vec![];
+        for (chunk_position, parts) in visible_parts {
+            let Some(mesh) = self.chunk_mesh_map.get(chunk_position) else { continue; };
+            if !mesh.parts_meshed.iter().all(|p| *p) { continue; }
+            meshes.push((chunk_position, mesh.clone(), parts));
+        }
+        meshes.into_boxed_slice()
+    }
     
+    pub fn sort_translucent_faces(&mut self, queue: &wgpu::Queue, camera_position: GlobalVecF) {
+        let camera_block_position = camera_position.chunk * CHUNK_SIZE_I32 + camera_position.local().map(|f| f.floor() as i32);
+        for (chunk_position, mesh) in self.chunk_mesh_map.iter_mut() {
+            for chunk_part_index in 0..PARTS_PER_CHUNK {
+                let chunk_part_origin = Vector3::new(chunk_position.0, chunk_part_index as i32, chunk_position.1) * CHUNK_SIZE_I32;
+                mesh.sort_translucent_faces(queue, chunk_part_index, camera_block_position - chunk_part_origin);
+            }
+        }
+    }
+
+    pub fn update_fade_in(&mut self, queue: &wgpu::Queue, fade_in_enabled: bool) {
+        for mesh in self.chunk_mesh_map.values_mut() {
+            mesh.update_fade_in(queue, fade_in_enabled);
+        }
+    }
+
     pub fn update(&mut self, device: &wgpu::Device) {
         self.chunk_generator.update(device, &mut self.chunk_map_lock.write(), &mut self.chunk_mesh_map);
     }
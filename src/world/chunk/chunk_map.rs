@@ -3,7 +3,7 @@ use std::sync::Arc;
 use cgmath::Vector2;
 use hashbrown::{hash_map::{Keys, Values, ValuesMut}, HashMap};
 
-use crate::{block::{light::LightLevel, Block}, chunk_position::ChunkPosition, global_vector::GlobalVecU};
+use crate::{block::{block_pallet::BlockPalletItemId, light::LightLevel, Block}, chunk_position::ChunkPosition, global_vector::GlobalVecU};
 
 use super::{chunk_generator::GenerationStage, Chunk, ChunkRef};
 use parking_lot::RwLock;
@@ -85,6 +85,13 @@ impl ChunkMap {
         chunk.set_block(position, block);
     }
 
+    #[inline]
+    pub fn get_block_pallet_id(&self, position: GlobalVecU) -> Option<BlockPalletItemId> {
+        let chunk = self.borrow_chunk(&position.chunk.xz())?;
+        let position = ChunkPosition::try_from(position).ok()?;
+        Some(chunk.get_block_pallet_id(position))
+    }
+
     #[inline]
     pub fn get_light_level(&self, position: GlobalVecU) -> Option<LightLevel> {
         let chunk = self.borrow_chunk(&position.chunk.xz())?;
@@ -2,9 +2,9 @@ use std::sync::mpsc::{Receiver, Sender};
 
 use cgmath::Vector2;
 
-use crate::{block::{light::LightLevel, model::{Face, FacePacked}, FaceDirection, FACE_DIRECTIONS_NUM}, BLOCK_MODEL_VARIANTS};
+use crate::{block::{model::{Face, FacePacked}, FaceDirection, ALPHA_MODES_NUM, FACE_DIRECTIONS_NUM}, assets};
 
-use super::{expanded_chunk_part::ExpandedChunkPart, CHUNK_SIZE, CHUNK_SIZE_U32};
+use super::{chunk_part_visibility::ChunkPartVisibility, expanded_chunk_part::ExpandedChunkPart, CHUNK_SIZE_U32};
 
 
 #[derive(Debug)]
@@ -15,8 +15,10 @@ pub struct MeshingInput {
 }
 
 pub struct MeshingOutput {
+    // faces grouped by alpha mode in the order of AlphaMode::ALL
     pub faces: Box<[FacePacked]>,
-    pub faces_num: usize,
+    pub faces_num_per_alpha_mode: [usize; ALPHA_MODES_NUM],
+    pub visibility: ChunkPartVisibility,
     pub chunk_position: Vector2<i32>,
     pub chunk_part_index: usize,
 }
@@ -34,12 +36,13 @@ impl ChunkPartMesher {
 
     fn run_mesher(receiver: Receiver<MeshingInput>, sender: Sender<MeshingOutput>) {
         for meshing_input in receiver.iter() {
-            let mut faces: Vec<FacePacked> = vec![];
+            let mut faces_per_alpha_mode: [Vec<FacePacked>; ALPHA_MODES_NUM] = std::array::from_fn(|_| vec![]);
 
             let max_block_pallet_id = meshing_input.expanded_chunk_part.block_pallet.ids().max().unwrap();
+            let assets = assets();
             let mut block_models_cache = vec![None; max_block_pallet_id as usize + 1];
             for (block_pallet_id, item) in meshing_input.expanded_chunk_part.block_pallet.iter() {
-                let variants = BLOCK_MODEL_VARIANTS.get_quad_block_models(&item.block).unwrap();
+                let variants = assets.block_model_variants.get_quad_block_models(&item.block).unwrap();
                 block_models_cache[block_pallet_id as usize] = Some(variants);
             }
 
@@ -87,7 +90,7 @@ impl ChunkPartMesher {
                                 };
                                 for (quad_index, texture_index, culling) in itertools::izip!(IntoIterator::into_iter(quad_indices), IntoIterator::into_iter(texture_indices), IntoIterator::into_iter(quad_culling)){
                                     if can_cull && *culling { continue; }
-                                    faces.push(Face {
+                                    faces_per_alpha_mode[block_properties.alpha_mode as usize].push(Face {
                                         block_position: [x as u8, y as u8, z as u8],
                                         lighting: [adjacent_block_light_level; 4],
                                         texture_index: *texture_index,
@@ -101,10 +104,16 @@ impl ChunkPartMesher {
                 }
             }
             
-            let faces_num = faces.len();
+            let visibility = ChunkPartVisibility::compute(|x, y, z| {
+                let block_pallet_id = meshing_input.expanded_chunk_part.index_inner_block_pallet_id((x, y, z));
+                block_properties_cache[*block_pallet_id as usize].unwrap().alpha_mode.is_opaque()
+            });
+
+            let faces_num_per_alpha_mode = std::array::from_fn(|i| faces_per_alpha_mode[i].len());
             let meshing_output = MeshingOutput {
-                faces: faces.into_boxed_slice(),
-                faces_num,
+                faces: faces_per_alpha_mode.concat().into_boxed_slice(),
+                faces_num_per_alpha_mode,
+                visibility,
                 chunk_position: meshing_input.chunk_position,
                 chunk_part_index: meshing_input.chunk_part_index,
             };
@@ -0,0 +1,114 @@
+use crate::block::{FaceDirection, FACE_DIRECTIONS_NUM};
+
+use super::{CHUNK_SIZE, CHUNK_SIZE_U32};
+
+// Face to face connectivity of a chunk part, bit (a * FACE_DIRECTIONS_NUM + b) is set
+// when face a can be seen through the part from face b
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct ChunkPartVisibility(u64);
+
+impl ChunkPartVisibility {
+    const VOLUME: usize = CHUNK_SIZE * CHUNK_SIZE * CHUNK_SIZE;
+
+    #[inline]
+    pub const fn none() -> Self {
+        Self(0)
+    }
+
+    #[inline]
+    pub const fn all() -> Self {
+        Self((1 << (FACE_DIRECTIONS_NUM * FACE_DIRECTIONS_NUM)) - 1)
+    }
+
+    #[inline]
+    pub fn is_connected(&self, a: FaceDirection, b: FaceDirection) -> bool {
+        self.0 & (1 << (a as usize * FACE_DIRECTIONS_NUM + b as usize)) != 0
+    }
+
+    #[inline]
+    pub fn set_connected(&mut self, a: FaceDirection, b: FaceDirection) {
+        self.0 |= 1 << (a as usize * FACE_DIRECTIONS_NUM + b as usize);
+        self.0 |= 1 << (b as usize * FACE_DIRECTIONS_NUM + a as usize);
+    }
+
+    #[inline]
+    fn convert_index(x: u32, y: u32, z: u32) -> usize {
+        x as usize + z as usize * CHUNK_SIZE + y as usize * CHUNK_SIZE * CHUNK_SIZE
+    }
+
+    #[inline]
+    fn touched_faces(x: u32, y: u32, z: u32) -> u8 {
+        let mut faces = 0;
+        if x == CHUNK_SIZE_U32 - 1 { faces |= 1 << FaceDirection::PositiveX as u8; }
+        if x == 0 { faces |= 1 << FaceDirection::NegativeX as u8; }
+        if z == CHUNK_SIZE_U32 - 1 { faces |= 1 << FaceDirection::PositiveZ as u8; }
+        if z == 0 { faces |= 1 << FaceDirection::NegativeZ as u8; }
+        if y == CHUNK_SIZE_U32 - 1 { faces |= 1 << FaceDirection::PositiveY as u8; }
+        if y == 0 { faces |= 1 << FaceDirection::NegativeY as u8; }
+        faces
+    }
+
+    // flood fills every region of non opaque blocks and connects all faces the region touches
+    pub fn compute<F: Fn(u32, u32, u32) -> bool>(is_opaque: F) -> Self {
+        let mut visited = vec![false; Self::VOLUME];
+        let mut opaque_count = 0;
+        for y in 0..CHUNK_SIZE_U32 {
+            for z in 0..CHUNK_SIZE_U32 {
+                for x in 0..CHUNK_SIZE_U32 {
+                    if is_opaque(x, y, z) {
+                        visited[Self::convert_index(x, y, z)] = true;
+                        opaque_count += 1;
+                    }
+                }
+            }
+        }
+
+        if opaque_count == 0 { return Self::all(); }
+        if opaque_count == Self::VOLUME { return Self::none(); }
+
+        let mut visibility = Self::none();
+        let mut stack = vec![];
+        for y in 0..CHUNK_SIZE_U32 {
+            for z in 0..CHUNK_SIZE_U32 {
+                for x in 0..CHUNK_SIZE_U32 {
+                    // only regions reaching the part's border matter
+                    if Self::touched_faces(x, y, z) == 0 { continue; }
+                    if visited[Self::convert_index(x, y, z)] { continue; }
+
+                    visited[Self::convert_index(x, y, z)] = true;
+                    stack.push((x, y, z));
+                    let mut faces = 0;
+                    while let Some((x, y, z)) = stack.pop() {
+                        faces |= Self::touched_faces(x, y, z);
+                        for face_direction in FaceDirection::ALL {
+                            let normal = face_direction.normal_i32();
+                            let (nx, ny, nz) = (x as i32 + normal.x, y as i32 + normal.y, z as i32 + normal.z);
+                            if !(0..CHUNK_SIZE as i32).contains(&nx) || !(0..CHUNK_SIZE as i32).contains(&ny) || !(0..CHUNK_SIZE as i32).contains(&nz) { continue; }
+                            let index = Self::convert_index(nx as u32, ny as u32, nz as u32);
+                            if visited[index] { continue; }
+                            visited[index] = true;
+                            stack.push((nx as u32, ny as u32, nz as u32));
+                        }
+                    }
+
+                    for a in FaceDirection::ALL {
+                        if faces & (1 << a as u8) == 0 { continue; }
+                        for b in FaceDirection::ALL {
+                            if faces & (1 << b as u8) == 0 { continue; }
+                            visibility.set_connected(a, b);
+                        }
+                    }
+                }
+            }
+        }
+
+        visibility
+    }
+}
+
+impl Default for ChunkPartVisibility {
+    #[inline]
+    fn default() -> Self {
+        Self::all()
+    }
+}
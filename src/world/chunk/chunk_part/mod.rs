@@ -1,14 +1,15 @@
 pub mod chunk_part_mesher;
 pub mod expanded_chunk_part;
 pub mod chunk_part_position;
+pub mod chunk_part_visibility;
 
-use std::ops::{Index, IndexMut};
+use std::ops::Index;
 
 use cgmath::Vector3;
 use chunk_part_position::ChunkPartPosition;
 use serde::Serialize;
 
-use crate::{block::{block_pallet::{BlockPallet, BlockPalletItemId}, light::{LightLevel, LightNode}, Block}, BLOCK_LIST};
+use crate::block::{block_pallet::{BlockPallet, BlockPalletItemId}, light::LightLevel, Block};
 
 pub const CHUNK_SIZE: usize = 32;
 pub const CHUNK_SIZE_F32: f32 = CHUNK_SIZE as f32;
@@ -77,6 +78,29 @@ impl ChunkPart {
         self.was_modified = true;
     }
 
+    // sets many blocks at once, the changes index into the blocks, so every block is looked up in the pallet only once.
+    // blocks that change how light passes through them start out like set_block leaves them, the light is spread afterwards
+    pub fn set_blocks(&mut self, blocks: &[Block], changes: &[(ChunkPartPosition, usize)]) {
+        let mut block_pallet_ids: Vec<Option<BlockPalletItemId>> = vec![None; blocks.len()];
+        for (local_position, block_index) in changes.iter().copied() {
+            let block = &blocks[block_index];
+            let block_pallet_id = *block_pallet_ids[block_index].get_or_insert_with(|| match self.block_pallet.get_block_pallet_id(block) {
+                Some(block_pallet_id) => block_pallet_id,
+                None => self.block_pallet.insert_count(block.clone(), 0),
+            });
+
+            let old_properties = self.get_block(local_position).properties();
+            self.set_block_pallet_id(local_position, block_pallet_id);
+
+            let properties = block.properties();
+            if !old_properties.lights_like(&properties) {
+                self.set_block_light_level(local_position, properties.emitted_light);
+                self.set_sky_light_level(local_position, 0);
+            }
+        }
+        self.block_pallet.clean_up();
+    }
+
     #[inline]
     pub fn get_block(&self, position: ChunkPartPosition) -> &Block {
         let block_pallet_id = self.block_layers.get_block_pallet_id(position);
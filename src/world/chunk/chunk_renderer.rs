@@ -1,46 +1,37 @@
-use crate::{block::quad_buffer::QuadBuffer, camera::ViewProjection, render_thread::RenderThread, shader::Shader, texture::Texture, utils::{bind_group_bundle::BindGroupBundle, index_buffer::IndexBuffer, render_pipeline_bundle::RenderPipelineBundle}, world::PARTS_PER_CHUNK, QUADS};
+use cgmath::{MetricSpace, Vector3};
 
-use super::{chunk_manager::ChunkManager, dynamic_chunk_mesh::DynamicChunkMesh, ChunkTranslation};
+use crate::{block::{quad_buffer::QuadBuffer, texture_animation::TextureFrameBuffer, AlphaMode, ALPHA_MODES_NUM}, camera::ViewProjection, fog::Fog, global_vector::GlobalVecF, settings::Settings, render_thread::RenderThread, shader::{capture_validation_errors, Shader}, texture::Texture, utils::{bind_group_bundle::BindGroupBundle, index_buffer::IndexBuffer, render_pipeline_bundle::RenderPipelineBundle}, world::PARTS_PER_CHUNK, assets};
+
+pub const SKY_COLOR: wgpu::Color = wgpu::Color { r: 123.0 / 255.0, g: 164.0 / 255.0, b: 1.0, a: 1.0 };
+const BLOCK_SHADER_PATH: &str = "./src/shaders/model.wgsl";
+
+use super::{chunk_part::CHUNK_SIZE_F32, chunk_manager::ChunkManager, dynamic_chunk_mesh::DynamicChunkMesh, ChunkTranslation};
 
 pub struct ChunkRenderer {
     textures_bind_group_bundle: BindGroupBundle,
-    texture_atlas: Texture,
+    block_textures: Texture,
+    texture_frame_buffer: TextureFrameBuffer,
     light_map: Texture,
     depth_texture: Texture,
-    block_render_pipeline_bundle: RenderPipelineBundle,
+    block_render_pipeline_shader: Shader,
+    block_render_pipeline_bundles: [RenderPipelineBundle; ALPHA_MODES_NUM],
     index_buffer: IndexBuffer,
     quad_buffer: QuadBuffer,
     pub view_projection: ViewProjection,
+    fog: Fog,
+    chunk_fade_in: bool,
     quad_buffer_bind_group_bundle: BindGroupBundle,
     view_projection_bind_group_bundle: BindGroupBundle,
 }
 
 impl ChunkRenderer {
-    pub fn new(device: &wgpu::Device, queue: &wgpu::Queue, surface_config: &wgpu::SurfaceConfiguration) -> anyhow::Result<Self> {
-        let texture_atlas = Texture::from_file(device, queue, "./assets/atlases/block_01.png")?;
+    pub fn new(device: &wgpu::Device, queue: &wgpu::Queue, surface_config: &wgpu::SurfaceConfiguration, settings: &Settings) -> anyhow::Result<Self> {
+        let assets = assets();
+        let block_textures = Texture::from_image_array(device, queue, &assets.texture_images, Some("ChunkRenderer_block_textures"));
         let light_map = Texture::from_file(device, queue, "./assets/atlases/light_map.png")?;
         let depth_texture = Texture::create_depth_texture(device, surface_config, "ChunkRenderer_depth_texture");
-
-        let textures_bind_group_bundle = {
-            let textures_bind_group_layout = device.create_bind_group_layout(&wgpu::BindGroupLayoutDescriptor {
-                label: Some("ChunkRenderer_textures_bind_group_layout"),
-                entries:
-                    texture_atlas.bind_group_layout_entries(0, 1).into_iter()
-                    .chain(light_map.bind_group_layout_entries(2, 3).into_iter())
-                    .collect::<Box<[wgpu::BindGroupLayoutEntry]>>().as_ref()
-            });
-
-            let textures_bind_group = device.create_bind_group(&wgpu::BindGroupDescriptor {
-                label: Some("ChunkRenderer_textures_bind_group_layout"),
-                layout: &textures_bind_group_layout,
-                entries: 
-                    texture_atlas.bind_group_entries(0, 1).into_iter()
-                    .chain(light_map.bind_group_entries(2, 3).into_iter())
-                    .collect::<Box<[wgpu::BindGroupEntry]>>().as_ref()
-            });
-
-            BindGroupBundle::new(textures_bind_group, textures_bind_group_layout)
-        };
+        let texture_frame_buffer = TextureFrameBuffer::new(device, assets.texture_images.len());
+        let textures_bind_group_bundle = Self::create_textures_bind_group_bundle(device, &block_textures, &light_map, &texture_frame_buffer);
 
         let index_buffer = {
             const INDICES: &[u32] = &[0, 1, 2,  1, 3, 2];
@@ -51,79 +42,174 @@ impl ChunkRenderer {
             IndexBuffer::new_init(device, &indices)
         };
 
-        let quad_buffer = QuadBuffer::new(&device, &QUADS);
+        let quad_buffer = QuadBuffer::new(&device, &assets.quads);
         let view_projection = ViewProjection::new(&device);
-
-        let quad_buffer_bind_group_bundle = {
-            let quad_buffer_bind_group_layout = device.create_bind_group_layout(&wgpu::BindGroupLayoutDescriptor {
-                label: Some("ChunkRenderer_quad_buffer_bind_group_layout"),
-                entries: &[quad_buffer.bind_group_layout_entry(0)],
-            });
-            
-            let quad_buffer_bind_group = device.create_bind_group(&wgpu::BindGroupDescriptor {
-                label: Some("ChunkRenderer_quad_buffer_bind_group"),
-                entries: &[quad_buffer.bind_group_entry(0)],
-                layout: &quad_buffer_bind_group_layout,
-            });
-
-            BindGroupBundle::new(quad_buffer_bind_group, quad_buffer_bind_group_layout)
-        };
+        let fog = Fog::new(device, SKY_COLOR);
+        let quad_buffer_bind_group_bundle = Self::create_quad_buffer_bind_group_bundle(device, &quad_buffer);
 
         let view_projection_bind_group_bundle = {
             let view_projection_bind_group_layout = device.create_bind_group_layout(&wgpu::BindGroupLayoutDescriptor {
                 label: Some("ChunkRenderer_view_projection_bind_group_layout"),
-                entries: &[view_projection.bind_group_layout_entry(0)],
+                entries: &[view_projection.bind_group_layout_entry(0), fog.bind_group_layout_entry(1)],
             });
 
             let view_projection_bind_group = device.create_bind_group(&wgpu::BindGroupDescriptor {
                 label: Some("ChunkRenderer_view_projection_bind_group"),
-                entries: &[view_projection.bind_group_entry(0)],
+                entries: &[view_projection.bind_group_entry(0), fog.bind_group_entry(1)],
                 layout: &view_projection_bind_group_layout,
             });
 
             BindGroupBundle::new(view_projection_bind_group, view_projection_bind_group_layout)
         };
 
+        let block_render_pipeline_shader = Shader::from_file(device, BLOCK_SHADER_PATH)?;
+        let block_render_pipeline_bundles = Self::create_block_render_pipeline_bundles(device, surface_config, &block_render_pipeline_shader, &textures_bind_group_bundle, &quad_buffer_bind_group_bundle, &view_projection_bind_group_bundle)?;
+
+        Ok(Self { textures_bind_group_bundle, block_textures, texture_frame_buffer, light_map, block_render_pipeline_shader, block_render_pipeline_bundles, index_buffer, quad_buffer, view_projection, fog, chunk_fade_in: settings.chunk_fade_in, quad_buffer_bind_group_bundle, view_projection_bind_group_bundle, depth_texture })
+    }
+
+    // re-uploads everything that comes from the assets after the resource packs are reloaded
+    pub fn reload_assets(&mut self, device: &wgpu::Device, queue: &wgpu::Queue, surface_config: &wgpu::SurfaceConfiguration) -> anyhow::Result<()> {
+        let assets = assets();
+        let block_textures = Texture::from_image_array(device, queue, &assets.texture_images, Some("ChunkRenderer_block_textures"));
+        let texture_frame_buffer = TextureFrameBuffer::new(device, assets.texture_images.len());
+        let textures_bind_group_bundle = Self::create_textures_bind_group_bundle(device, &block_textures, &self.light_map, &texture_frame_buffer);
+        let quad_buffer = QuadBuffer::new(device, &assets.quads);
+        let quad_buffer_bind_group_bundle = Self::create_quad_buffer_bind_group_bundle(device, &quad_buffer);
+        let block_render_pipeline_bundles = Self::create_block_render_pipeline_bundles(device, surface_config, &self.block_render_pipeline_shader, &textures_bind_group_bundle, &quad_buffer_bind_group_bundle, &self.view_projection_bind_group_bundle)?;
+
+        self.block_textures = block_textures;
+        self.texture_frame_buffer = texture_frame_buffer;
+        self.textures_bind_group_bundle = textures_bind_group_bundle;
+        self.quad_buffer = quad_buffer;
+        self.quad_buffer_bind_group_bundle = quad_buffer_bind_group_bundle;
+        self.block_render_pipeline_bundles = block_render_pipeline_bundles;
+        Ok(())
+    }
+
+    pub fn shader_depends_on(&self, changed_files: &[std::path::PathBuf]) -> bool {
+        self.block_render_pipeline_shader.depends_on(changed_files)
+    }
+
+    // rebuilds the pipelines from the shader's current source, the old ones are kept if it doesn't compile
+    pub fn reload_shaders(&mut self, device: &wgpu::Device, surface_config: &wgpu::SurfaceConfiguration) -> anyhow::Result<()> {
+        let block_render_pipeline_shader = Shader::from_file(device, BLOCK_SHADER_PATH)?;
+        let block_render_pipeline_bundles = capture_validation_errors(device, || Self::create_block_render_pipeline_bundles(device, surface_config, &block_render_pipeline_shader, &self.textures_bind_group_bundle, &self.quad_buffer_bind_group_bundle, &self.view_projection_bind_group_bundle))??;
+
+        self.block_render_pipeline_shader = block_render_pipeline_shader;
+        self.block_render_pipeline_bundles = block_render_pipeline_bundles;
+        Ok(())
+    }
+
+    fn create_textures_bind_group_bundle(device: &wgpu::Device, block_textures: &Texture, light_map: &Texture, texture_frame_buffer: &TextureFrameBuffer) -> BindGroupBundle {
+        let textures_bind_group_layout = device.create_bind_group_layout(&wgpu::BindGroupLayoutDescriptor {
+            label: Some("ChunkRenderer_textures_bind_group_layout"),
+            entries:
+                block_textures.bind_group_layout_entries(0, 1).into_iter()
+                .chain(light_map.bind_group_layout_entries(2, 3).into_iter())
+                .chain(std::iter::once(texture_frame_buffer.bind_group_layout_entry(4)))
+                .collect::<Box<[wgpu::BindGroupLayoutEntry]>>().as_ref()
+        });
+
+        let textures_bind_group = device.create_bind_group(&wgpu::BindGroupDescriptor {
+            label: Some("ChunkRenderer_textures_bind_group_layout"),
+            layout: &textures_bind_group_layout,
+            entries: 
+                block_textures.bind_group_entries(0, 1).into_iter()
+                .chain(light_map.bind_group_entries(2, 3).into_iter())
+                .chain(std::iter::once(texture_frame_buffer.bind_group_entry(4)))
+                .collect::<Box<[wgpu::BindGroupEntry]>>().as_ref()
+        });
+
+        BindGroupBundle::new(textures_bind_group, textures_bind_group_layout)
+    }
+
+    fn create_quad_buffer_bind_group_bundle(device: &wgpu::Device, quad_buffer: &QuadBuffer) -> BindGroupBundle {
+        let quad_buffer_bind_group_layout = device.create_bind_group_layout(&wgpu::BindGroupLayoutDescriptor {
+            label: Some("ChunkRenderer_quad_buffer_bind_group_layout"),
+            entries: &[quad_buffer.bind_group_layout_entry(0)],
+        });
+        
+        let quad_buffer_bind_group = device.create_bind_group(&wgpu::BindGroupDescriptor {
+            label: Some("ChunkRenderer_quad_buffer_bind_group"),
+            entries: &[quad_buffer.bind_group_entry(0)],
+            layout: &quad_buffer_bind_group_layout,
+        });
+
+        BindGroupBundle::new(quad_buffer_bind_group, quad_buffer_bind_group_layout)
+    }
+
+    fn create_block_render_pipeline_bundles(device: &wgpu::Device, surface_config: &wgpu::SurfaceConfiguration, block_render_pipeline_shader: &Shader, textures_bind_group_bundle: &BindGroupBundle, quad_buffer_bind_group_bundle: &BindGroupBundle, view_projection_bind_group_bundle: &BindGroupBundle) -> anyhow::Result<[RenderPipelineBundle; ALPHA_MODES_NUM]> {
+        let side_shade_texture_index = assets().texture_map.get("side_shade").ok_or_else(|| anyhow::anyhow!("missing side_shade texture"))?;
+        let block_render_pipeline_constants = std::collections::HashMap::from([
+            ("SIDE_SHADE_TEXTURE_INDEX".to_string(), side_shade_texture_index as f64),
+        ]);
+
+        Ok(AlphaMode::ALL.map(|alpha_mode| {
+            Self::create_block_render_pipeline_bundle(
+                device,
+                surface_config,
+                block_render_pipeline_shader,
+                &block_render_pipeline_constants,
+                &[
+                    textures_bind_group_bundle.layout(),
+                    quad_buffer_bind_group_bundle.layout(),
+                    view_projection_bind_group_bundle.layout(),
+                    DynamicChunkMesh::get_or_init_face_buffer_bind_group_layout(device),
+                    ChunkTranslation::get_or_init_bind_group_layout(device),
+                ],
+                alpha_mode
+            )
+        }))
+    }
+
+    pub fn depth_texture(&self) -> &Texture {
+        &self.depth_texture
+    }
+
+    pub fn view_projection_bind_group_bundle(&self) -> &BindGroupBundle {
+        &self.view_projection_bind_group_bundle
+    }
+
+    fn create_block_render_pipeline_bundle(device: &wgpu::Device, surface_config: &wgpu::SurfaceConfiguration, shader: &Shader, constants: &std::collections::HashMap<String, f64>, bind_group_layouts: &[&wgpu::BindGroupLayout], alpha_mode: AlphaMode) -> RenderPipelineBundle {
+        let (fragment_entry_point, blend, depth_write_enabled) = match alpha_mode {
+            AlphaMode::Opaque => ("fs_opaque", wgpu::BlendState::REPLACE, true),
+            AlphaMode::Transparent => ("fs_cutout", wgpu::BlendState::REPLACE, true),
+            AlphaMode::Translucent => ("fs_translucent", wgpu::BlendState::ALPHA_BLENDING, false),
+        };
+
         let block_render_pipeline_layout = device.create_pipeline_layout(&wgpu::PipelineLayoutDescriptor {
             label: Some("ChunkRenderer_block_render_pipeline_layout"),
             push_constant_ranges: &[],
-            bind_group_layouts: &[
-                textures_bind_group_bundle.layout(),
-                quad_buffer_bind_group_bundle.layout(),
-                view_projection_bind_group_bundle.layout(),
-                DynamicChunkMesh::get_or_init_face_buffer_bind_group_layout(device),
-                ChunkTranslation::get_or_init_bind_group_layout(device),
-            ]
+            bind_group_layouts,
         });
 
-        let block_render_pipeline_shader = Shader::from_file(device, "./src/shaders/model.wgsl")?;
-
         let block_render_pipeline = device.create_render_pipeline(&wgpu::RenderPipelineDescriptor {
             label: Some("ChunkRenderer_block_render_pipeline"),
             depth_stencil: Some(wgpu::DepthStencilState {
                 bias: wgpu::DepthBiasState::default(),
                 depth_compare: wgpu::CompareFunction::Less,
-                depth_write_enabled: true,
+                depth_write_enabled,
                 format: Texture::DEPTH_FORMAT,
                 stencil: wgpu::StencilState::default()
             }),
             vertex: wgpu::VertexState {
                 buffers: &[],
                 entry_point: "vs_main",
-                module: block_render_pipeline_shader.module(),
-                compilation_options: wgpu::PipelineCompilationOptions::default(),
+                module: shader.module(),
+                compilation_options: wgpu::PipelineCompilationOptions { constants, ..Default::default() },
             },
             fragment: Some(wgpu::FragmentState {
-                entry_point: "fs_main",
-                module: block_render_pipeline_shader.module(),
+                entry_point: fragment_entry_point,
+                module: shader.module(),
                 targets: &[Some(
                     wgpu::ColorTargetState {
                         format: surface_config.format,
-                        blend: Some(wgpu::BlendState::REPLACE),
+                        blend: Some(blend),
                         write_mask: wgpu::ColorWrites::all()
                     }
                 )],
-                compilation_options: wgpu::PipelineCompilationOptions::default(),
+                compilation_options: wgpu::PipelineCompilationOptions { constants, ..Default::default() },
             }),
             layout: Some(&block_render_pipeline_layout),
             multisample: wgpu::MultisampleState {
@@ -143,25 +229,49 @@ impl ChunkRenderer {
             },
         });
 
-        let block_render_pipeline_bundle = RenderPipelineBundle::new(block_render_pipeline, block_render_pipeline_layout);
+        RenderPipelineBundle::new(block_render_pipeline, block_render_pipeline_layout)
+    }
 
-        Ok(Self { textures_bind_group_bundle, texture_atlas, light_map, block_render_pipeline_bundle, index_buffer, quad_buffer, view_projection, quad_buffer_bind_group_bundle, view_projection_bind_group_bundle, depth_texture })
+    pub fn set_daylight(&mut self, daylight: f32) {
+        self.fog.set_daylight(daylight);
     }
 
-    pub fn render(&self, device: &wgpu::Device, queue: &wgpu::Queue, chunk_manager: &mut ChunkManager, render_thread: &mut RenderThread) {
+    pub fn render(&mut self, device: &wgpu::Device, queue: &wgpu::Queue, chunk_manager: &mut ChunkManager, render_thread: &mut RenderThread, camera_position: GlobalVecF) {
         chunk_manager.collect_meshing_outputs(device, queue);
+        chunk_manager.sort_translucent_faces(queue, camera_position);
+        chunk_manager.update_fade_in(queue, self.chunk_fade_in);
+        self.fog.update(camera_position.into(), chunk_manager.render_radius() as f32 * CHUNK_SIZE_F32);
+        self.texture_frame_buffer.update(&assets().texture_animations);
+
+        let meshes = chunk_manager.get_visible_meshes(camera_position.chunk);
+
+        // translucent faces are blended, so the parts have to be drawn back to front
+        let camera_chunk_fractional_position = camera_position.chunk_fractional_position();
+        let mut translucent_parts = vec![];
+        for (mesh_index, (chunk_position, mesh, visible_parts)) in meshes.iter().enumerate() {
+            for (chunk_part_index, _) in visible_parts.iter().enumerate().filter(|(_, f)| **f) {
+                if !mesh.has_faces(chunk_part_index, AlphaMode::Translucent) { continue; }
+                let chunk_part_center = Vector3::new(chunk_position.x as f32, chunk_part_index as f32, chunk_position.y as f32) + Vector3::new(0.5, 0.5, 0.5);
+                translucent_parts.push((camera_chunk_fractional_position.distance2(chunk_part_center), mesh_index, chunk_part_index));
+            }
+        }
+        translucent_parts.sort_by(|a, b| b.0.total_cmp(&a.0));
 
-        let meshes = chunk_manager.get_ready_meshes();
         let view_projection = self.view_projection.clone();
+        let fog = self.fog.clone();
+        let sky_color = fog.color();
+        let texture_frame_buffer = self.texture_frame_buffer.clone();
         let textures_bind_group_bundle = self.textures_bind_group_bundle.clone();
         let quad_buffer_bind_group_bundle = self.quad_buffer_bind_group_bundle.clone();
         let view_projection_bind_group_bundle = self.view_projection_bind_group_bundle.clone();
         let depth_texture = self.depth_texture.clone();
-        let render_pipeline_bundle = self.block_render_pipeline_bundle.clone();
+        let render_pipeline_bundles = self.block_render_pipeline_bundles.clone();
         let index_buffer = self.index_buffer.clone();
 
         render_thread.push_render(move |queue, encoder, view| {
             view_projection.update_buffer(queue);
+            fog.update_buffer(queue);
+            texture_frame_buffer.update_buffer(queue);
 
             let mut render_pass = encoder.begin_render_pass(&wgpu::RenderPassDescriptor {
                 label: Some("ChunkRenderBundle_render_pass"),
@@ -169,7 +279,7 @@ impl ChunkRenderer {
                     view,
                     resolve_target: None,
                     ops: wgpu::Operations {
-                        load: wgpu::LoadOp::Clear(wgpu::Color { r: 123.0 / 255.0, g: 164.0 / 255.0, b: 1.0, a: 1.0 }),
+                        load: wgpu::LoadOp::Clear(sky_color),
                         store: wgpu::StoreOp::Store
                     }
                 })],
@@ -182,19 +292,38 @@ impl ChunkRenderer {
                 occlusion_query_set: None
             });
 
-            render_pass.set_pipeline(render_pipeline_bundle.render_pipeline());
             render_pass.set_bind_group(0, textures_bind_group_bundle.bind_group(), &[]);
             render_pass.set_bind_group(1, quad_buffer_bind_group_bundle.bind_group(), &[]);
             render_pass.set_bind_group(2, view_projection_bind_group_bundle.bind_group(), &[]);
             render_pass.set_index_buffer(index_buffer.buffer().slice(..), IndexBuffer::FORMAT);
 
-            for mesh in meshes.iter() {
-                if !mesh.parts_meshed.iter().cloned().all(|f| f) { continue; }
+            for alpha_mode in [AlphaMode::Opaque, AlphaMode::Transparent] {
+                render_pass.set_pipeline(render_pipeline_bundles[alpha_mode as usize].render_pipeline());
+
+                for (_, mesh, visible_parts) in meshes.iter() {
+                    if !mesh.parts_meshed.iter().cloned().all(|f| f) { continue; }
+
+                    render_pass.set_bind_group(3, mesh.face_buffer_bind_group(), &[]);
+                    render_pass.set_bind_group(4, mesh.translation().bind_group(), &[]);
+
+                    if visible_parts.iter().all(|f| *f) {
+                        render_pass.multi_draw_indexed_indirect(mesh.indirect_buffer(), DynamicChunkMesh::indirect_args_offset(alpha_mode, 0), PARTS_PER_CHUNK as u32);
+                        continue;
+                    }
+
+                    for (chunk_part_index, _) in visible_parts.iter().enumerate().filter(|(_, f)| **f) {
+                        render_pass.draw_indexed_indirect(mesh.indirect_buffer(), DynamicChunkMesh::indirect_args_offset(alpha_mode, chunk_part_index));
+                    }
+                }
+            }
+
+            render_pass.set_pipeline(render_pipeline_bundles[AlphaMode::Translucent as usize].render_pipeline());
+            for (_, mesh_index, chunk_part_index) in translucent_parts {
+                let mesh = &meshes[mesh_index].1;
 
                 render_pass.set_bind_group(3, mesh.face_buffer_bind_group(), &[]);
                 render_pass.set_bind_group(4, mesh.translation().bind_group(), &[]);
-
-                render_pass.multi_draw_indexed_indirect(mesh.indirect_buffer(), 0, PARTS_PER_CHUNK as u32);
+                render_pass.draw_indexed_indirect(mesh.indirect_buffer(), DynamicChunkMesh::indirect_args_offset(AlphaMode::Translucent, chunk_part_index));
             }
         });
     }
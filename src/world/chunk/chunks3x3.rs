@@ -3,7 +3,7 @@ use std::{collections::VecDeque, fmt::Debug, mem::MaybeUninit, sync::Arc};
 use cgmath::{num_traits::Euclid, Vector2, Vector3};
 use hashbrown::{HashMap, HashSet};
 
-use crate::{block::{light::{LightLevel, LIGHT_LEVEL_MAX_VALUE}, Block, FaceDirection}, chunk_position::ChunkPosition, global_vector::GlobalVecU, world::{chunk::chunk_part::CHUNK_SIZE_I32, structure::Structure, CHUNK_HEIGHT, PARTS_PER_CHUNK}, BLOCK_LIST};
+use crate::{block::{light::LIGHT_LEVEL_MAX_VALUE, Block}, chunk_position::ChunkPosition, world::{structure::Structure, CHUNK_HEIGHT}};
 
 use super::{chunk_map::{ChunkMap, ChunkMapLock}, chunk_part::{chunk_part_position::ChunkPartPosition, ChunkPart, CHUNK_SIZE, CHUNK_SIZE_U32}, Chunk, ChunkRef};
 
@@ -292,9 +292,17 @@ impl Chunks3x3 {
         self.step_block_light_removal_towards(position, Vector3::new(0, 1, 0), &mut removal_queue, &mut propagation_queue);
         self.step_block_light_removal_towards(position, Vector3::new(0, -1, 0), &mut removal_queue, &mut propagation_queue);
 
-        while let Some(light_node) = removal_queue.pop_back() {
-            let position = Vector3::new(light_node.x as i32, light_node.y as i32, light_node.z as i32);
+        self.drain_block_light_removal(&mut removal_queue, &mut propagation_queue);
+        self.drain_block_light_propagation(&mut propagation_queue);
+    }
+
+    // the same as removing and then propagating the block light at every position one after the other,
+    // but all of them share one removal and one propagation so the light around them is only spread once
+    pub fn update_block_light_levels_at(&mut self, positions: &[Vector3<i32>]) {
+        let mut propagation_queue = VecDeque::new();
+        let mut removal_queue = VecDeque::new();
 
+        for position in positions.iter().copied() {
             self.step_block_light_removal_towards(position, Vector3::new(1, 0, 0), &mut removal_queue, &mut propagation_queue);
             self.step_block_light_removal_towards(position, Vector3::new(-1, 0, 0), &mut removal_queue, &mut propagation_queue);
             self.step_block_light_removal_towards(position, Vector3::new(0, 0, 1), &mut removal_queue, &mut propagation_queue);
@@ -302,7 +310,26 @@ impl Chunks3x3 {
             self.step_block_light_removal_towards(position, Vector3::new(0, 1, 0), &mut removal_queue, &mut propagation_queue);
             self.step_block_light_removal_towards(position, Vector3::new(0, -1, 0), &mut removal_queue, &mut propagation_queue);
         }
+        self.drain_block_light_removal(&mut removal_queue, &mut propagation_queue);
+
+        propagation_queue.extend(positions.iter().map(|position| LightNode::new(*position)));
+        self.drain_block_light_propagation(&mut propagation_queue);
+    }
 
+    fn drain_block_light_removal(&mut self, removal_queue: &mut VecDeque<LightNode>, propagation_queue: &mut VecDeque<LightNode>) {
+        while let Some(light_node) = removal_queue.pop_back() {
+            let position = Vector3::new(light_node.x as i32, light_node.y as i32, light_node.z as i32);
+
+            self.step_block_light_removal_towards(position, Vector3::new(1, 0, 0), removal_queue, propagation_queue);
+            self.step_block_light_removal_towards(position, Vector3::new(-1, 0, 0), removal_queue, propagation_queue);
+            self.step_block_light_removal_towards(position, Vector3::new(0, 0, 1), removal_queue, propagation_queue);
+            self.step_block_light_removal_towards(position, Vector3::new(0, 0, -1), removal_queue, propagation_queue);
+            self.step_block_light_removal_towards(position, Vector3::new(0, 1, 0), removal_queue, propagation_queue);
+            self.step_block_light_removal_towards(position, Vector3::new(0, -1, 0), removal_queue, propagation_queue);
+        }
+    }
+
+    fn drain_block_light_propagation(&mut self, propagation_queue: &mut VecDeque<LightNode>) {
         let mut visited_nodes: HashMap<LightNode, u8> = HashMap::new();
         while let Some(light_node) = propagation_queue.pop_front() {
             let position = Vector3::new(light_node.x as i32, light_node.y as i32, light_node.z as i32);
@@ -322,12 +349,12 @@ impl Chunks3x3 {
                 }
             }
 
-            self.step_block_light_propagation_towards(position, Vector3::new(1, 0, 0), light_level, &mut propagation_queue);
-            self.step_block_light_propagation_towards(position, Vector3::new(-1, 0, 0), light_level, &mut propagation_queue);
-            self.step_block_light_propagation_towards(position, Vector3::new(0, 0, 1), light_level, &mut propagation_queue);
-            self.step_block_light_propagation_towards(position, Vector3::new(0, 0, -1), light_level, &mut propagation_queue);
-            self.step_block_light_propagation_towards(position, Vector3::new(0, 1, 0), light_level, &mut propagation_queue);
-            self.step_block_light_propagation_towards(position, Vector3::new(0, -1, 0), light_level, &mut propagation_queue);
+            self.step_block_light_propagation_towards(position, Vector3::new(1, 0, 0), light_level, propagation_queue);
+            self.step_block_light_propagation_towards(position, Vector3::new(-1, 0, 0), light_level, propagation_queue);
+            self.step_block_light_propagation_towards(position, Vector3::new(0, 0, 1), light_level, propagation_queue);
+            self.step_block_light_propagation_towards(position, Vector3::new(0, 0, -1), light_level, propagation_queue);
+            self.step_block_light_propagation_towards(position, Vector3::new(0, 1, 0), light_level, propagation_queue);
+            self.step_block_light_propagation_towards(position, Vector3::new(0, -1, 0), light_level, propagation_queue);
         }
     }
 
@@ -411,15 +438,22 @@ impl Chunks3x3 {
     }
 
     pub fn update_sky_light_level_at(&mut self, position: Vector3<i32>) {
+        self.update_sky_light_levels_at(&[position]);
+    }
+
+    // the removal and the propagation are shared by all the positions so the light around them is only spread once
+    pub fn update_sky_light_levels_at(&mut self, positions: &[Vector3<i32>]) {
         let mut propagation_queue = VecDeque::new();
         let mut removal_queue = VecDeque::new();
 
-        self.step_sky_light_removal_towards(position, Vector3::new(1, 0, 0), &mut removal_queue, &mut propagation_queue);
-        self.step_sky_light_removal_towards(position, Vector3::new(-1, 0, 0), &mut removal_queue, &mut propagation_queue);
-        self.step_sky_light_removal_towards(position, Vector3::new(0, 0, 1), &mut removal_queue, &mut propagation_queue);
-        self.step_sky_light_removal_towards(position, Vector3::new(0, 0, -1), &mut removal_queue, &mut propagation_queue);
-        self.step_sky_light_removal_towards(position, Vector3::new(0, 1, 0), &mut removal_queue, &mut propagation_queue);
-        self.step_sky_light_removal_towards(position, Vector3::new(0, -1, 0), &mut removal_queue, &mut propagation_queue);
+        for position in positions.iter().copied() {
+            self.step_sky_light_removal_towards(position, Vector3::new(1, 0, 0), &mut removal_queue, &mut propagation_queue);
+            self.step_sky_light_removal_towards(position, Vector3::new(-1, 0, 0), &mut removal_queue, &mut propagation_queue);
+            self.step_sky_light_removal_towards(position, Vector3::new(0, 0, 1), &mut removal_queue, &mut propagation_queue);
+            self.step_sky_light_removal_towards(position, Vector3::new(0, 0, -1), &mut removal_queue, &mut propagation_queue);
+            self.step_sky_light_removal_towards(position, Vector3::new(0, 1, 0), &mut removal_queue, &mut propagation_queue);
+            self.step_sky_light_removal_towards(position, Vector3::new(0, -1, 0), &mut removal_queue, &mut propagation_queue);
+        }
 
         while let Some(light_node) = removal_queue.pop_front() {
             let position = Vector3::new(light_node.x as i32, light_node.y as i32, light_node.z as i32);
@@ -1,14 +1,15 @@
 use std::sync::Arc;
 
-use cgmath::Vector2;
+use cgmath::{Vector2, Vector3};
 
-use crate::{block::model::{Face, FacePacked}, world::PARTS_PER_CHUNK};
+use crate::{block::{model::{Face, FacePacked}, AlphaMode, ALPHA_MODES_NUM}, world::PARTS_PER_CHUNK};
 
-use super::{chunk_part::{chunk_part_mesher::MeshingOutput, CHUNK_SIZE}, ChunkTranslation};
+use super::{chunk_part::{chunk_part_mesher::MeshingOutput, chunk_part_visibility::ChunkPartVisibility, CHUNK_SIZE}, ChunkTranslation};
 
 static FACE_BUFFER_BIND_GROUP_LAYOUT: std::sync::OnceLock<wgpu::BindGroupLayout> = std::sync::OnceLock::new();
 
-// Model mesh that is divided into buckets containing each chunk's faces
+// Model mesh that is divided into buckets containing each chunk's faces,
+// every bucket holds the part's faces grouped by alpha mode
 #[derive(Clone)]
 pub struct DynamicChunkMesh {
     face_buffer: Arc<wgpu::Buffer>,
@@ -18,12 +19,19 @@ pub struct DynamicChunkMesh {
     pub parts_meshed: [bool; PARTS_PER_CHUNK],
     pub parts_meshing_scheduled: [bool; PARTS_PER_CHUNK],
     pub parts_need_meshing: [bool; PARTS_PER_CHUNK],
+    parts_visibility: [ChunkPartVisibility; PARTS_PER_CHUNK],
+    faces_num_per_alpha_mode: [[usize; ALPHA_MODES_NUM]; PARTS_PER_CHUNK],
+    translucent_faces: [Arc<[FacePacked]>; PARTS_PER_CHUNK],
+    translucent_sort_origins: [Option<Vector3<i32>>; PARTS_PER_CHUNK],
     translation: Arc<ChunkTranslation>,
+    meshed_at: Option<std::time::Instant>,
+    fade_in_finished: bool,
 }
 
 impl DynamicChunkMesh {
     pub const MIN_BUCKET_SIZE: u32 = Self::MIN_BUCKET_ELEMENTS * std::mem::size_of::<Face>() as u32;
     pub const MIN_BUCKET_ELEMENTS: u32 = 64;
+    pub const FADE_IN_DURATION: std::time::Duration = std::time::Duration::from_millis(500);
 
     pub fn new(device: &wgpu::Device, chunk_position: Vector2<i32>) -> Self {
         let face_buffer = Self::create_face_buffer(device, (Self::MIN_BUCKET_SIZE as usize * PARTS_PER_CHUNK) as u64);
@@ -35,9 +43,13 @@ impl DynamicChunkMesh {
         let parts_meshed = std::array::from_fn(|_| false);
         let parts_meshing_scheduled = std::array::from_fn(|_| false);
         let parts_need_meshing = std::array::from_fn(|_| false);
+        let parts_visibility = std::array::from_fn(|_| ChunkPartVisibility::all());
+        let faces_num_per_alpha_mode = [[0; ALPHA_MODES_NUM]; PARTS_PER_CHUNK];
+        let translucent_faces = std::array::from_fn(|_| Arc::from([]));
+        let translucent_sort_origins = [None; PARTS_PER_CHUNK];
         let translation = Arc::new(ChunkTranslation::new(device, chunk_position));
 
-        Self { face_buffer, indirect_buffer, face_bucket_elements, face_buffer_bind_group, parts_meshed, parts_meshing_scheduled, translation, parts_need_meshing }
+        Self { face_buffer, indirect_buffer, face_bucket_elements, face_buffer_bind_group, parts_meshed, parts_meshing_scheduled, translation, parts_need_meshing, parts_visibility, faces_num_per_alpha_mode, translucent_faces, translucent_sort_origins, meshed_at: None, fade_in_finished: false }
     }
 
     pub fn face_buffer(&self) -> &wgpu::Buffer {
@@ -56,6 +68,22 @@ impl DynamicChunkMesh {
         &self.translation
     }
 
+    #[inline]
+    pub fn part_visibility(&self, chunk_part_index: usize) -> ChunkPartVisibility {
+        self.parts_visibility[chunk_part_index]
+    }
+
+    #[inline]
+    pub fn has_faces(&self, chunk_part_index: usize, alpha_mode: AlphaMode) -> bool {
+        self.faces_num_per_alpha_mode[chunk_part_index][alpha_mode as usize] > 0
+    }
+
+    // indirect args are laid out per alpha mode, so each mode can be drawn for every part in one call
+    #[inline]
+    pub fn indirect_args_offset(alpha_mode: AlphaMode, chunk_part_index: usize) -> u64 {
+        ((alpha_mode as usize * PARTS_PER_CHUNK + chunk_part_index) * std::mem::size_of::<wgpu::util::DrawIndexedIndirectArgs>()) as u64
+    }
+
     pub fn get_or_init_face_buffer_bind_group_layout(device: &wgpu::Device) -> &wgpu::BindGroupLayout {
         FACE_BUFFER_BIND_GROUP_LAYOUT.get_or_init(|| {
             device.create_bind_group_layout(&wgpu::BindGroupLayoutDescriptor {
@@ -89,7 +117,7 @@ impl DynamicChunkMesh {
         Arc::new(device.create_buffer(&wgpu::BufferDescriptor {
             label: Some("DynamicRegionModelMesh_indirect_buffer"),
             mapped_at_creation: false,
-            size: (std::mem::size_of::<wgpu::util::DrawIndexedIndirectArgs>() * PARTS_PER_CHUNK) as u64,
+            size: (std::mem::size_of::<wgpu::util::DrawIndexedIndirectArgs>() * PARTS_PER_CHUNK * ALPHA_MODES_NUM) as u64,
             usage: wgpu::BufferUsages::INDIRECT | wgpu::BufferUsages::COPY_DST,
         }))
     }
@@ -119,9 +147,13 @@ impl DynamicChunkMesh {
         self.indirect_buffer = Self::create_indirect_buffer(device);
     }
 
-    pub fn create_indirect_args(&self, faces_num: usize, chunk_part_index: usize) -> wgpu::util::DrawIndexedIndirectArgs {
+    fn bucket_start(&self, chunk_part_index: usize) -> u32 {
+        self.face_bucket_elements[0..chunk_part_index].iter().sum::<u32>()
+    }
+
+    pub fn create_indirect_args(&self, faces_num: usize, faces_offset: usize, chunk_part_index: usize) -> wgpu::util::DrawIndexedIndirectArgs {
         wgpu::util::DrawIndexedIndirectArgs {
-            base_vertex: (self.face_bucket_elements[0..chunk_part_index].iter().sum::<u32>() * Face::VERTICES_PER_FACE as u32) as i32,
+            base_vertex: ((self.bucket_start(chunk_part_index) + faces_offset as u32) * Face::VERTICES_PER_FACE as u32) as i32,
             first_index: 0,
             first_instance: (chunk_part_index * CHUNK_SIZE) as u32,
             index_count: (faces_num * Face::INDICES_PER_FACE) as u32,
@@ -147,14 +179,63 @@ impl DynamicChunkMesh {
             }
         }
 
-        let indirect_buffer_offset = (chunk_part_index * std::mem::size_of::<wgpu::util::DrawIndexedIndirectArgs>()) as u64;
-        let face_buffer_offset = ((0..chunk_part_index).map(|i| self.face_bucket_elements[i]).sum::<u32>() * std::mem::size_of::<FacePacked>() as u32) as u64;
-        if meshing_data.faces_num > 0 {
+        let face_buffer_offset = (self.bucket_start(chunk_part_index) * std::mem::size_of::<FacePacked>() as u32) as u64;
+        if !meshing_data.faces.is_empty() {
             queue.write_buffer(&self.face_buffer, face_buffer_offset, bytemuck::cast_slice(&meshing_data.faces));
         }
-        queue.write_buffer(&self.indirect_buffer, indirect_buffer_offset, self.create_indirect_args(meshing_data.faces_num, chunk_part_index).as_bytes());
 
+        let mut faces_offset = 0;
+        for alpha_mode in AlphaMode::ALL {
+            let faces_num = meshing_data.faces_num_per_alpha_mode[alpha_mode as usize];
+            let indirect_args = self.create_indirect_args(faces_num, faces_offset, chunk_part_index);
+            queue.write_buffer(&self.indirect_buffer, Self::indirect_args_offset(alpha_mode, chunk_part_index), indirect_args.as_bytes());
+            faces_offset += faces_num;
+        }
+
+        let translucent_faces_start = meshing_data.faces.len() - meshing_data.faces_num_per_alpha_mode[AlphaMode::Translucent as usize];
+        self.translucent_faces[chunk_part_index] = Arc::from(&meshing_data.faces[translucent_faces_start..]);
+        self.translucent_sort_origins[chunk_part_index] = None;
+        self.faces_num_per_alpha_mode[chunk_part_index] = meshing_data.faces_num_per_alpha_mode;
+
+        self.parts_visibility[chunk_part_index] = meshing_data.visibility;
         self.parts_meshed[chunk_part_index] = true;
+        if self.meshed_at.is_none() && self.parts_meshed.iter().all(|f| *f) {
+            self.meshed_at = Some(std::time::Instant::now());
+        }
         self.parts_meshing_scheduled[chunk_part_index] = false;
     }
+
+    // fades the chunk out of the fog after it gets meshed for the first time
+    pub fn update_fade_in(&mut self, queue: &wgpu::Queue, fade_in_enabled: bool) {
+        if self.fade_in_finished { return; }
+        let Some(meshed_at) = self.meshed_at else { return; };
+
+        let fade_in = if fade_in_enabled {
+            (meshed_at.elapsed().as_secs_f32() / Self::FADE_IN_DURATION.as_secs_f32()).min(1.0)
+        } else {
+            1.0
+        };
+        self.translation.update_fade_in(queue, fade_in);
+        self.fade_in_finished = fade_in >= 1.0;
+    }
+
+    // origin is the camera's block position relative to the chunk part,
+    // the faces are only rewritten when the camera moves to another block
+    pub fn sort_translucent_faces(&mut self, queue: &wgpu::Queue, chunk_part_index: usize, origin: Vector3<i32>) {
+        if self.translucent_faces[chunk_part_index].is_empty() || self.translucent_sort_origins[chunk_part_index] == Some(origin) { return; }
+
+        let mut faces = self.translucent_faces[chunk_part_index].to_vec();
+        faces.sort_by_cached_key(|face| {
+            let block_position = face.block_position();
+            let offset = Vector3::new(block_position[0] as i32, block_position[1] as i32, block_position[2] as i32) - origin;
+            std::cmp::Reverse(offset.x * offset.x + offset.y * offset.y + offset.z * offset.z)
+        });
+
+        let faces_num_per_alpha_mode = self.faces_num_per_alpha_mode[chunk_part_index];
+        let translucent_faces_offset = faces_num_per_alpha_mode[AlphaMode::Opaque as usize] + faces_num_per_alpha_mode[AlphaMode::Transparent as usize];
+        let face_buffer_offset = ((self.bucket_start(chunk_part_index) + translucent_faces_offset as u32) * std::mem::size_of::<FacePacked>() as u32) as u64;
+        queue.write_buffer(&self.face_buffer, face_buffer_offset, bytemuck::cast_slice(&faces));
+
+        self.translucent_sort_origins[chunk_part_index] = Some(origin);
+    }
 }
\ No newline at end of file
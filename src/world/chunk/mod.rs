@@ -5,7 +5,7 @@ use chunk_generator::GenerationStage;
 use chunk_part::{chunk_part_position::ChunkPartPosition, ChunkPart, CHUNK_SIZE};
 use wgpu::util::DeviceExt;
 
-use crate::{block::{light::LightLevel, Block}, chunk_position::ChunkPosition};
+use crate::{block::{block_pallet::BlockPalletItemId, light::LightLevel, Block}, chunk_position::ChunkPosition};
 
 use super::{CHUNK_HEIGHT, PARTS_PER_CHUNK};
 
@@ -133,6 +133,12 @@ impl Chunk {
         chunk_part.get_block(position.chunk_part_position())
     }
 
+    #[inline]
+    pub fn get_block_pallet_id(&self, position: ChunkPosition) -> BlockPalletItemId {
+        let chunk_part = &self.parts[position.chunk_part_index()];
+        *chunk_part.block_layers.get_block_pallet_id(position.chunk_part_position())
+    }
+
     #[inline]
     pub fn set_block(&mut self, position: ChunkPosition, block: Block) {
         let highest_block_position = &mut self.highest_blocks[position.chunk_part_position().xz()];
@@ -146,6 +152,31 @@ impl Chunk {
         chunk_part.set_block(position.chunk_part_position(), block);
     }
 
+    // sets the blocks of one part in a batch, then looks for the highest blocks of the touched columns again
+    pub fn set_blocks_in_part(&mut self, chunk_part_index: usize, blocks: &[Block], changes: &[(ChunkPartPosition, usize)]) {
+        self.parts[chunk_part_index].set_blocks(blocks, changes);
+
+        let mut touched_columns = changes.iter().map(|(position, _)| position.position().xz()).collect::<Vec<Vector2<u8>>>();
+        touched_columns.sort_unstable_by_key(|column| (column.x, column.y));
+        touched_columns.dedup();
+        for column in touched_columns {
+            self.highest_blocks[column] = Self::find_highest_block(&self.parts, column);
+        }
+    }
+
+    #[inline]
+    fn find_highest_block(parts: &[ChunkPart; PARTS_PER_CHUNK], column: Vector2<u8>) -> HighestBlockPosition {
+        for (chunk_part_index, chunk_part) in parts.iter().enumerate().rev() {
+            for y in (0..CHUNK_SIZE as u8).rev() {
+                let chunk_part_position = unsafe { ChunkPartPosition::new_unchecked(Vector3::new(column.x as u32, y as u32, column.y as u32)) };
+                if !chunk_part.get_block(chunk_part_position).is_air() {
+                    return HighestBlockPosition { chunk_part_index: chunk_part_index as u8, y };
+                }
+            }
+        }
+        HighestBlockPosition::default()
+    }
+
     #[inline]
     fn find_new_highest_block(parts: &[ChunkPart; PARTS_PER_CHUNK], position: ChunkPosition, prev_highest_block_position: &mut HighestBlockPosition) {
         let chunk_part = &parts[position.chunk_part_index()];
@@ -242,8 +273,9 @@ impl ChunkTranslation {
     pub fn new(device: &wgpu::Device, position: Vector2<i32>) -> Self {
         let buffer = device.create_buffer_init(&wgpu::util::BufferInitDescriptor {
             label: Some("ChunkTranslation_buffer"),
-            contents: bytemuck::cast_slice(&[position.x, position.y]),
-            usage: wgpu::BufferUsages::UNIFORM,
+            // position, fade in and padding
+            contents: bytemuck::cast_slice(&[position.x, position.y, 0, 0]),
+            usage: wgpu::BufferUsages::UNIFORM | wgpu::BufferUsages::COPY_DST,
         });
         
         let bind_group_layout = CHUNK_TRANSLATION_BIND_GROUP_LAYOUT.get_or_init(|| Self::create_bind_group_layout(device));
@@ -293,6 +325,10 @@ impl ChunkTranslation {
     pub fn bind_group(&self) -> &wgpu::BindGroup {
         &self.bind_group
     }
+
+    pub fn update_fade_in(&self, queue: &wgpu::Queue, fade_in: f32) {
+        queue.write_buffer(&self.buffer, std::mem::size_of::<[i32; 2]>() as u64, bytemuck::bytes_of(&fade_in));
+    }
 }
 
 impl Drop for ChunkTranslation {
@@ -0,0 +1,57 @@
+// What the player is allowed to do, it's switched with G or in the debug gui, creative players toggle flying with F
+#[derive(serde::Deserialize, serde::Serialize, Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum GameMode {
+    // flies, stops at blocks and breaks them instantly
+    #[default]
+    Creative,
+    // falls, stops at blocks and breaks them over time by their hardness
+    Survival,
+    // flies through blocks and can't change them
+    Spectator,
+}
+
+impl GameMode {
+    pub const ALL: [GameMode; 3] = [GameMode::Creative, GameMode::Survival, GameMode::Spectator];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::Creative => "creative",
+            Self::Survival => "survival",
+            Self::Spectator => "spectator",
+        }
+    }
+
+    pub fn next(&self) -> Self {
+        match self {
+            Self::Creative => Self::Survival,
+            Self::Survival => Self::Spectator,
+            Self::Spectator => Self::Creative,
+        }
+    }
+
+    #[inline]
+    pub fn can_fly(&self) -> bool {
+        *self != Self::Survival
+    }
+
+    // spectators would fall through the ground
+    #[inline]
+    pub fn always_flies(&self) -> bool {
+        *self == Self::Spectator
+    }
+
+    #[inline]
+    pub fn collides_with_blocks(&self) -> bool {
+        *self != Self::Spectator
+    }
+
+    #[inline]
+    pub fn can_modify_blocks(&self) -> bool {
+        *self != Self::Spectator
+    }
+
+    #[inline]
+    pub fn breaks_instantly(&self) -> bool {
+        *self == Self::Creative
+    }
+}
@@ -0,0 +1,85 @@
+use crate::{block::{block_state::BlockState, Block}, assets};
+
+pub const HOTBAR_SLOTS: usize = 9;
+
+// A block in a hotbar slot, it's kept by name so the hotbar stays valid when the assets are reloaded
+#[derive(serde::Deserialize, serde::Serialize, Debug, Clone, PartialEq)]
+pub struct HotbarItem {
+    pub block_name: String,
+    // a picked block keeps its exact state, otherwise the block starts in its default state and gets its placement states
+    pub block_state: Option<BlockState>,
+}
+
+impl HotbarItem {
+    pub fn new(block_name: &str) -> Self {
+        Self { block_name: block_name.to_string(), block_state: None }
+    }
+
+    // None when the block no longer exists
+    pub fn block(&self) -> Option<Block> {
+        let mut block: Block = assets().block_map.get(&self.block_name)?.clone().into();
+        if let Some(block_state) = &self.block_state {
+            block.block_state = block_state.clone();
+            block.refresh_properties();
+        }
+        Some(block)
+    }
+}
+
+// The blocks the player can place
+#[derive(serde::Deserialize, serde::Serialize, Debug, Clone)]
+pub struct Hotbar {
+    slots: [Option<HotbarItem>; HOTBAR_SLOTS],
+    selected_slot: usize,
+}
+
+impl Default for Hotbar {
+    fn default() -> Self {
+        let mut slots: [Option<HotbarItem>; HOTBAR_SLOTS] = Default::default();
+        for (slot, block_name) in slots.iter_mut().zip(["torch", "stone", "cobblestone", "dirt", "grass", "oak_log", "oak_leaves"]) {
+            *slot = Some(HotbarItem::new(block_name));
+        }
+        Self { slots, selected_slot: 0 }
+    }
+}
+
+impl Hotbar {
+    #[inline]
+    pub fn selected_slot(&self) -> usize {
+        self.selected_slot
+    }
+
+    pub fn select(&mut self, slot: usize) {
+        if slot < HOTBAR_SLOTS {
+            self.selected_slot = slot;
+        }
+    }
+
+    // moves the selection by the number of scrolled steps, wrapping around the ends
+    pub fn scroll(&mut self, steps: i32) {
+        self.selected_slot = (self.selected_slot as i32 - steps).rem_euclid(HOTBAR_SLOTS as i32) as usize;
+    }
+
+    #[inline]
+    pub fn slot(&self, slot: usize) -> Option<&HotbarItem> {
+        self.slots.get(slot)?.as_ref()
+    }
+
+    #[inline]
+    pub fn selected_item(&self) -> Option<&HotbarItem> {
+        self.slot(self.selected_slot)
+    }
+
+    pub fn set_selected(&mut self, item: HotbarItem) {
+        self.slots[self.selected_slot] = Some(item);
+    }
+
+    // selects the slot that already holds the exact block or puts the block in the selected slot
+    pub fn pick(&mut self, block: &Block) {
+        let item = HotbarItem { block_name: block.name().to_string(), block_state: Some(block.block_state.clone()) };
+        match self.slots.iter().position(|slot| slot.as_ref() == Some(&item)) {
+            Some(slot) => self.selected_slot = slot,
+            None => self.set_selected(item),
+        }
+    }
+}
@@ -0,0 +1,99 @@
+use std::path::Path;
+
+use cgmath::{Vector2, Vector3};
+use hashbrown::HashMap;
+use image::{Rgba, RgbaImage};
+
+use crate::block::Block;
+
+use super::{chunk::{chunk_part::{chunk_part_position::ChunkPartPosition, CHUNK_SIZE}, Chunk}, region::Region, structure::{block_from_key, block_key}, CHUNK_HEIGHT};
+
+// a rendered map can't be wider or taller than this many pixels
+const MAX_MAP_SIZE: u32 = 16384;
+
+// The highest block of a column as it's seen from above
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct MapColumn {
+    pub height: u32,
+    pub color: [u8; 3],
+}
+
+// the highest block of the column and its height, None when the column is only air
+pub fn surface_block(chunk: &Chunk, column: Vector2<u8>) -> Option<(u32, &Block)> {
+    let highest_block = chunk.highest_blocks[column];
+    let chunk_part_position = unsafe { ChunkPartPosition::new_unchecked(Vector3::new(column.x as u32, highest_block.y as u32, column.y as u32)) };
+    let block = chunk.parts[highest_block.chunk_part_index as usize].get_block(chunk_part_position);
+    // a column that's only air keeps the lowest position
+    if block.is_air() { return None; }
+    Some((highest_block.chunk_part_index as u32 * CHUNK_SIZE as u32 + highest_block.y as u32, block))
+}
+
+pub fn map_column(chunk: &Chunk, column: Vector2<u8>, mut map_color: impl FnMut(&Block) -> [u8; 3]) -> Option<MapColumn> {
+    surface_block(chunk, column).map(|(height, block)| MapColumn { height, color: map_color(block) })
+}
+
+// brighter where the column is higher than the one north of it and darker where it's lower so slopes can be seen,
+// higher ground is a little brighter overall
+pub fn shade(column: MapColumn, north_height: Option<u32>) -> [u8; 3] {
+    let slope = match north_height {
+        Some(north_height) if column.height > north_height => 1.1,
+        Some(north_height) if column.height < north_height => 0.8,
+        _ => 0.95,
+    };
+    let elevation = 0.8 + 0.4 * column.height as f32 / CHUNK_HEIGHT as f32;
+    column.color.map(|f| (f as f32 * slope * elevation).round().min(255.0) as u8)
+}
+
+// renders every region in the directory from above with north up, one pixel per block.
+// the colors come from the current assets because the properties in the saves can be older,
+// chunks that aren't saved and columns that are only air are transparent
+pub fn render_regions<P: AsRef<Path>>(directory: P) -> anyhow::Result<RgbaImage> {
+    let mut colors: HashMap<String, [u8; 3]> = HashMap::new();
+    let mut map_color = |block: &Block| {
+        let key = block_key(block);
+        *colors.entry(key).or_insert_with_key(|key| block_from_key(key).unwrap_or_else(|| block.clone()).properties().map_color)
+    };
+
+    let mut chunk_columns: HashMap<Vector2<i32>, Box<[Option<MapColumn>]>> = HashMap::new();
+    for entry in std::fs::read_dir(&directory)? {
+        let entry = entry?;
+        let Some(position) = entry.file_name().to_str().and_then(Region::file_name_to_position) else { continue; };
+        let region = Region::load(&directory, position).map_err(|err| anyhow::anyhow!("couldn't load the region {}: {}", entry.path().display(), err))?;
+        for chunk in region.chunks.read().iter_chunks() {
+            let mut columns = vec![None; CHUNK_SIZE * CHUNK_SIZE].into_boxed_slice();
+            for z in 0..CHUNK_SIZE as u8 {
+                for x in 0..CHUNK_SIZE as u8 {
+                    columns[x as usize + z as usize * CHUNK_SIZE] = map_column(chunk, Vector2::new(x, z), &mut map_color);
+                }
+            }
+            chunk_columns.insert(chunk.position, columns);
+        }
+    }
+
+    let Some(first_position) = chunk_columns.keys().next().copied() else { anyhow::bail!("there are no saved chunks in {}", directory.as_ref().display()); };
+    let (min, max) = chunk_columns.keys().fold((first_position, first_position), |(min, max), position| {
+        (min.zip(*position, i32::min), max.zip(*position, i32::max))
+    });
+    let size = (max - min).map(|f| (f as u64 + 1) * CHUNK_SIZE as u64);
+    if size.x > MAX_MAP_SIZE as u64 || size.y > MAX_MAP_SIZE as u64 {
+        anyhow::bail!("the map would be {}x{} pixels, it can be at most {} pixels wide on every side", size.x, size.y, MAX_MAP_SIZE);
+    }
+
+    let mut image = RgbaImage::new(size.x as u32, size.y as u32);
+    for (position, columns) in chunk_columns.iter() {
+        let north_columns = chunk_columns.get(&(position - Vector2::new(0, 1)));
+        let offset = (position - min).map(|f| f as u32 * CHUNK_SIZE as u32);
+        for z in 0..CHUNK_SIZE {
+            for x in 0..CHUNK_SIZE {
+                let Some(column) = columns[x + z * CHUNK_SIZE] else { continue; };
+                let north_column = match z {
+                    0 => north_columns.and_then(|north_columns| north_columns[x + (CHUNK_SIZE - 1) * CHUNK_SIZE]),
+                    _ => columns[x + (z - 1) * CHUNK_SIZE],
+                };
+                let [r, g, b] = shade(column, north_column.map(|f| f.height));
+                image.put_pixel(offset.x + x as u32, offset.y + z as u32, Rgba([r, g, b, 255]));
+            }
+        }
+    }
+    Ok(image)
+}
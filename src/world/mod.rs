@@ -1,5 +1,13 @@
-use chunk::{chunk_manager::ChunkManager, chunk_part::CHUNK_SIZE, chunk_renderer::ChunkRenderer};
-use player::Player;
+use cgmath::{Vector2, Vector3};
+use hashbrown::HashMap;
+use chunk::{chunk_generator::GenerationStage, chunk_manager::ChunkManager, chunk_part::CHUNK_SIZE, chunk_renderer::ChunkRenderer, Chunk};
+use block_outline_renderer::BlockOutlineRenderer;
+use block_breaking_renderer::BlockBreakingRenderer;
+use player::{Player, PLAYER_SAVE_PATH};
+use region::{Region, REGION_SIZE};
+use spawn::{Spawn, SPAWN_SAVE_PATH};
+use world_edit::WorldEdit;
+use world_time::WorldTime;
 
 use crate::settings::Settings;
 
@@ -7,24 +15,149 @@ pub mod chunk;
 pub mod structure;
 pub mod player;
 pub mod region;
+pub mod raycast;
+pub mod block_outline_renderer;
+pub mod block_breaking_renderer;
+pub mod block_overlay_renderer;
+pub mod hotbar;
+pub mod game_mode;
+pub mod world_time;
+pub mod world_edit;
+pub mod map;
+pub mod spawn;
 
 pub const CHUNK_HEIGHT: usize = CHUNK_SIZE * PARTS_PER_CHUNK;
 pub const PARTS_PER_CHUNK: usize = 12;
+pub const REGIONS_SAVE_DIRECTORY: &str = "./save/regions";
 
 pub struct World {
     pub chunk_manager: ChunkManager,
     pub chunk_renderer: ChunkRenderer,
+    pub block_outline_renderer: BlockOutlineRenderer,
+    pub block_breaking_renderer: BlockBreakingRenderer,
     pub player: Player,
+    pub time: WorldTime,
+    pub world_edit: WorldEdit,
+    pub spawn: Spawn,
+    // a player that wasn't saved is moved to the spawn once it's found
+    is_player_waiting_for_spawn: bool,
 }
 
 impl World {
     pub fn new(device: &wgpu::Device, queue: &wgpu::Queue, surface_config: &wgpu::SurfaceConfiguration, settings: &Settings) -> anyhow::Result<Self> {
-        let chunk_renderer = ChunkRenderer::new(device, queue, surface_config)?;
+        let chunk_renderer = ChunkRenderer::new(device, queue, surface_config, settings)?;
+        let block_outline_renderer = BlockOutlineRenderer::new(device, surface_config, &chunk_renderer)?;
+        let block_breaking_renderer = BlockBreakingRenderer::new(device, surface_config, &chunk_renderer)?;
 
+        let player = Self::load_player(settings.render_distance);
         Ok(Self {
             chunk_manager: ChunkManager::new(settings.render_distance, 8, 4),
             chunk_renderer,
-            player: Player::new(),
+            block_outline_renderer,
+            block_breaking_renderer,
+            is_player_waiting_for_spawn: player.is_none(),
+            player: player.unwrap_or_else(|| Player::new(settings.render_distance)),
+            time: WorldTime::default(),
+            world_edit: WorldEdit::new(),
+            spawn: Self::load_spawn(),
         })
     }
+
+    // a player that was never saved starts at the spawn, one that can't be read too but it's reported
+    fn load_player(render_distance: u32) -> Option<Player> {
+        match Player::load(PLAYER_SAVE_PATH, render_distance) {
+            Ok(player) => Some(player),
+            Err(err) => {
+                if !is_not_found(&err) {
+                    println!("couldn't load the player, starting at the spawn: {}", err);
+                }
+                None
+            }
+        }
+    }
+
+    fn load_spawn() -> Spawn {
+        match Spawn::load(SPAWN_SAVE_PATH) {
+            Ok(spawn) => spawn,
+            Err(err) => {
+                if !is_not_found(&err) {
+                    println!("couldn't load the spawn, searching for a new one: {}", err);
+                }
+                Spawn::Searching
+            }
+        }
+    }
+
+    #[inline]
+    pub fn is_player_waiting_for_spawn(&self) -> bool {
+        self.is_player_waiting_for_spawn
+    }
+
+    // searches for the spawn until it's found and moves a waiting player there.
+    // a saved player can be far from the origin whose chunks then never load, so it's searched for around them
+    pub fn update_spawn(&mut self) {
+        if self.spawn == Spawn::Searching {
+            let center = match self.is_player_waiting_for_spawn {
+                true => Vector2::new(0, 0),
+                false => self.player.position.chunk.xz(),
+            };
+            let Some(position) = Spawn::search(&self.chunk_manager.chunk_map_lock.read(), center) else { return; };
+            if let Err(err) = self.set_spawn(position) {
+                println!("couldn't save the spawn: {}", err);
+            }
+        }
+        if let Some(position) = self.spawn.position().filter(|_| self.is_player_waiting_for_spawn) {
+            self.player.move_feet_to(position);
+            self.is_player_waiting_for_spawn = false;
+        }
+    }
+
+    pub fn set_spawn(&mut self, position: Vector3<i32>) -> anyhow::Result<()> {
+        self.spawn = Spawn::Found(position);
+        self.spawn.save(SPAWN_SAVE_PATH)
+    }
+
+    pub fn set_render_distance(&mut self, render_distance: u32) {
+        self.chunk_manager.set_render_radius(render_distance);
+        self.player.render_distance = render_distance;
+    }
+
+    // saves the player and the fully generated chunks grouped into regions, returns the number of saved chunks
+    pub fn save(&self) -> anyhow::Result<usize> {
+        self.player.save(PLAYER_SAVE_PATH)?;
+        self.spawn.save(SPAWN_SAVE_PATH)?;
+        std::fs::create_dir_all(REGIONS_SAVE_DIRECTORY)?;
+
+        let mut chunks_per_region: HashMap<Vector2<i32>, Vec<Chunk>> = HashMap::new();
+        for chunk_ref in self.chunk_manager.chunk_map_lock.read().iter_chunks() {
+            if chunk_ref.generation_stage != GenerationStage::LAST_GENERATION_STAGE { continue; }
+            let mut chunk = Chunk::clone(chunk_ref);
+            chunk.maintain_parts();
+            let region_position = chunk.position.map(|f| f.div_euclid(REGION_SIZE as i32));
+            chunks_per_region.entry(region_position).or_default().push(chunk);
+        }
+
+        let mut saved_chunks = 0;
+        for (region_position, chunks) in chunks_per_region {
+            // the chunks that were saved before and aren't loaded anymore are kept
+            let region = match Region::load(REGIONS_SAVE_DIRECTORY, region_position) {
+                Ok(region) => region,
+                Err(err) if is_not_found(&err) => Region::new(region_position),
+                Err(err) => anyhow::bail!("couldn't load the region {}_{} to save into it: {}", region_position.x, region_position.y, err),
+            };
+            saved_chunks += chunks.len();
+            let mut region_chunks = region.chunks.write();
+            for chunk in chunks {
+                region_chunks.insert(chunk);
+            }
+            drop(region_chunks);
+            region.save(REGIONS_SAVE_DIRECTORY)?;
+        }
+        Ok(saved_chunks)
+    }
+}
+
+// a save that doesn't exist yet isn't an error
+fn is_not_found(err: &anyhow::Error) -> bool {
+    err.downcast_ref::<std::io::Error>().is_some_and(|err| err.kind() == std::io::ErrorKind::NotFound)
 }
@@ -1,9 +1,9 @@
-use cgmath::{Deg, InnerSpace, Point3, Rad, Vector2, Vector3};
+use cgmath::{Deg, InnerSpace, Point3, Rad, Vector2, Vector3, Zero};
 use winit::{event::MouseButton, keyboard::KeyCode};
 
-use crate::{block::Block, camera::Camera, collision::bounding_box::{GlobalBoundingBox, Ray}, global_vector::{GlobalVecF, GlobalVecU}, world::{chunk::chunk_part::{CHUNK_SIZE, CHUNK_SIZE_U32}, PARTS_PER_CHUNK}, BLOCK_LIST, BLOCK_MAP, BLOCK_MODEL_VARIANTS};
+use crate::{block::placement::BlockPlacementContext, camera::Camera, collision::bounding_box::{LocalBoundingBox, Ray}, global_vector::{GlobalVecF, GlobalVecU}, world::{chunk::{chunk_generator::GenerationStage, chunk_map::ChunkMap, chunk_part::{CHUNK_SIZE, CHUNK_SIZE_F32}}, CHUNK_HEIGHT}, assets};
 
-use super::chunk::chunk_manager::ChunkManager;
+use super::{game_mode::GameMode, hotbar::Hotbar, chunk::chunk_manager::ChunkManager, raycast::{block_local_hitboxes, raycast_block, BlockRaycastResult}};
 
 pub struct Player {
     pub position: GlobalVecF,
@@ -20,10 +20,68 @@ pub struct Player {
     pub is_right_mouse_pressed: bool,
     pub is_r_pressed: bool,
     pub last_block_modification: std::time::Instant,
+    pub render_distance: u32,
+    pub hotbar: Hotbar,
+    // the scrolling that hasn't added up to a whole hotbar slot yet, trackpads scroll in small steps
+    scroll_remainder: f32,
+    pub mining: Option<MiningProgress>,
+    last_mining_update: std::time::Instant,
+    // blocks break as soon as they're clicked regardless of their hardness, it's toggled with B
+    pub instant_break: bool,
+    pub game_mode: GameMode,
+    // creative players can stop flying, spectators always fly
+    pub is_flying: bool,
+    // only used when the player isn't flying
+    pub vertical_velocity: f32,
+    pub on_ground: bool,
 }
+
+pub const CRACK_STAGES: u32 = 10;
+
+// How far the player got with breaking the block at the position, it starts over when the target changes
+#[derive(Debug, Clone, Copy)]
+pub struct MiningProgress {
+    pub position: GlobalVecU,
+    // the block breaks when it reaches 1
+    pub progress: f32,
+}
+
+impl MiningProgress {
+    pub fn crack_stage(&self) -> u32 {
+        ((self.progress * CRACK_STAGES as f32) as u32).min(CRACK_STAGES - 1)
+    }
+}
+
+// the part of the player that's kept between sessions
+#[derive(serde::Deserialize, serde::Serialize)]
+struct PlayerSave {
+    position: Vector3<f64>,
+    yaw: Deg<f32>,
+    pitch: Deg<f32>,
+    hotbar: Hotbar,
+    #[serde(default)]
+    game_mode: GameMode,
+    // older saves fly when their game mode can
+    #[serde(default)]
+    is_flying: Option<bool>,
+}
+
 const PITCH_LIMIT: f32 = 90.0 - 0.0001;
+const PLAYER_REACH: f32 = 5.0;
+const PLAYER_WIDTH: f32 = 0.6;
+const PLAYER_HEIGHT: f32 = 1.8;
+// the position is at the eyes, this far above the feet
+const PLAYER_EYE_HEIGHT: f32 = 1.62;
+const GRAVITY: f32 = 28.0;
+const TERMINAL_VELOCITY: f32 = 60.0;
+// enough to jump a bit higher than a block
+const JUMP_VELOCITY: f32 = 8.6;
+// a long frame would let the player fall through blocks
+const MAX_UPDATE_DT: f32 = 0.05;
+pub const PLAYER_SAVE_PATH: &str = "./save/player";
+pub const PLAYER_AUTOSAVE_INTERVAL: std::time::Duration = std::time::Duration::from_secs(30);
 impl Player {
-    pub fn new() -> Self {
+    pub fn new(render_distance: u32) -> Self {
         let yaw = Deg(90.0_f32);
         let pitch = Deg(0.0_f32);
         
@@ -51,9 +109,51 @@ impl Player {
             is_right_mouse_pressed: false,
             is_r_pressed: false,
             last_block_modification: std::time::Instant::now(),
+            render_distance,
+            hotbar: Hotbar::default(),
+            scroll_remainder: 0.0,
+            mining: None,
+            last_mining_update: std::time::Instant::now(),
+            instant_break: false,
+            game_mode: GameMode::default(),
+            is_flying: GameMode::default().can_fly(),
+            vertical_velocity: 0.0,
+            on_ground: false,
         }
     }
 
+    pub fn save<P: AsRef<std::path::Path>>(&self, path: P) -> anyhow::Result<()> {
+        if let Some(directory) = path.as_ref().parent() {
+            std::fs::create_dir_all(directory)?;
+        }
+        let player_save = PlayerSave {
+            position: self.position.into(),
+            yaw: self.yaw,
+            pitch: self.pitch,
+            hotbar: self.hotbar.clone(),
+            game_mode: self.game_mode,
+            is_flying: Some(self.is_flying),
+        };
+        // the old save is only replaced once the new one is written completely
+        let temporary_path = path.as_ref().with_extension("tmp");
+        std::fs::write(&temporary_path, rmp_serde::to_vec(&player_save)?)?;
+        std::fs::rename(temporary_path, path)?;
+        Ok(())
+    }
+
+    pub fn load<P: AsRef<std::path::Path>>(path: P, render_distance: u32) -> anyhow::Result<Self> {
+        let player_save: PlayerSave = rmp_serde::from_slice(&std::fs::read(path)?)?;
+        let mut player = Self::new(render_distance);
+        player.position = GlobalVecF::from(player_save.position);
+        player.yaw = player_save.yaw;
+        player.pitch = player_save.pitch;
+        player.hotbar = player_save.hotbar;
+        player.game_mode = player_save.game_mode;
+        player.is_flying = player_save.game_mode.always_flies() || (player_save.game_mode.can_fly() && player_save.is_flying.unwrap_or(true));
+        player.handle_mouse_movement(Vector2::new(0.0, 0.0));
+        Ok(player)
+    }
+
     pub fn handle_mouse_movement(&mut self, delta: Vector2<f32>) {
         self.yaw += Deg(delta.x / 8.0).into();
         self.pitch -= Deg(delta.y / 8.0).into();
@@ -80,6 +180,16 @@ impl Player {
             KeyCode::Space => { self.is_up_pressed = pressed },
             KeyCode::KeyR => { self.is_r_pressed = pressed }
             KeyCode::ShiftLeft => { self.is_down_pressed = pressed },
+            KeyCode::Digit1 if pressed => { self.hotbar.select(0) },
+            KeyCode::Digit2 if pressed => { self.hotbar.select(1) },
+            KeyCode::Digit3 if pressed => { self.hotbar.select(2) },
+            KeyCode::Digit4 if pressed => { self.hotbar.select(3) },
+            KeyCode::Digit5 if pressed => { self.hotbar.select(4) },
+            KeyCode::Digit6 if pressed => { self.hotbar.select(5) },
+            KeyCode::Digit7 if pressed => { self.hotbar.select(6) },
+            KeyCode::Digit8 if pressed => { self.hotbar.select(7) },
+            KeyCode::Digit9 if pressed => { self.hotbar.select(8) },
+            KeyCode::KeyB if pressed => { self.instant_break = !self.instant_break },
             _ => ()
         }
     }
@@ -88,8 +198,10 @@ impl Player {
         match button {
             MouseButton::Left => {
                 self.is_left_mouse_pressed = pressed;
+                self.mining = None;
                 if pressed {
                     self.last_block_modification = std::time::Instant::now();
+                    self.last_mining_update = std::time::Instant::now();
                 }
             },
             MouseButton::Right => {
@@ -102,7 +214,36 @@ impl Player {
         }
     }
 
-    pub fn update(&mut self, dt: f32) {
+    pub fn set_game_mode(&mut self, game_mode: GameMode) {
+        self.game_mode = game_mode;
+        self.is_flying = game_mode.can_fly();
+        self.vertical_velocity = 0.0;
+        self.on_ground = false;
+        self.mining = None;
+    }
+
+    // the block the player's feet are in
+    pub fn feet_block_position(&self) -> Vector3<i32> {
+        let feet: GlobalVecU = (self.position - Vector3::new(0.0, PLAYER_EYE_HEIGHT, 0.0)).into();
+        feet.block_position()
+    }
+
+    // puts the player's feet in the middle of the block's bottom
+    pub fn move_feet_to(&mut self, position: Vector3<i32>) {
+        self.position = GlobalVecF::from(position.map(|f| f as f64) + Vector3::new(0.5, PLAYER_EYE_HEIGHT as f64, 0.5));
+        self.vertical_velocity = 0.0;
+        self.on_ground = false;
+    }
+
+    pub fn toggle_flying(&mut self) {
+        if !self.game_mode.can_fly() || self.game_mode.always_flies() { return; }
+        self.is_flying = !self.is_flying;
+        self.vertical_velocity = 0.0;
+        self.on_ground = false;
+    }
+
+    pub fn update(&mut self, dt: f32, chunk_manager: &ChunkManager) {
+        let dt = dt.min(MAX_UPDATE_DT);
         let forward = Vector3::new(self.direction.x, 0.0, self.direction.z).normalize();
         let right = forward.cross(Vector3::unit_y());
 
@@ -127,91 +268,197 @@ impl Player {
         }
 
 
-        if self.is_up_pressed {
-            vertical_movement_vector += Vector3::unit_y();
-        }
+        if self.is_flying {
+            if self.is_up_pressed {
+                vertical_movement_vector += Vector3::unit_y();
+            }
 
-        if self.is_down_pressed {
-            vertical_movement_vector -= Vector3::unit_y();
+            if self.is_down_pressed {
+                vertical_movement_vector -= Vector3::unit_y();
+            }
+            vertical_movement_vector *= speed;
+        } else {
+            if self.is_up_pressed && self.on_ground {
+                self.vertical_velocity = JUMP_VELOCITY;
+            }
+            self.vertical_velocity = (self.vertical_velocity - GRAVITY * dt).max(-TERMINAL_VELOCITY);
+            vertical_movement_vector.y = self.vertical_velocity * dt;
         }
 
 
         if horizontal_movement_vector.magnitude2() > 0.0 {
-            horizontal_movement_vector = horizontal_movement_vector.normalize();
-            self.position += horizontal_movement_vector * speed;
+            horizontal_movement_vector = horizontal_movement_vector.normalize() * speed;
+        }
+
+        let movement = horizontal_movement_vector + vertical_movement_vector;
+        if !self.game_mode.collides_with_blocks() {
+            self.position += movement;
+            return;
+        }
+
+        let moved = self.move_colliding(movement, &chunk_manager.chunk_map_lock.read());
+        self.on_ground = movement.y < 0.0 && moved.y > movement.y;
+        // stops falling on the ground and jumping into a ceiling
+        if moved.y != movement.y {
+            self.vertical_velocity = 0.0;
+        }
+    }
+
+    // the player's hitbox relative to the origin
+    fn local_hitbox(&self, origin: GlobalVecU) -> LocalBoundingBox {
+        let eyes: Vector3<f32> = (self.position - origin).into();
+        LocalBoundingBox {
+            start: eyes - Vector3::new(PLAYER_WIDTH / 2.0, PLAYER_EYE_HEIGHT, PLAYER_WIDTH / 2.0),
+            end: eyes + Vector3::new(PLAYER_WIDTH / 2.0, PLAYER_HEIGHT - PLAYER_EYE_HEIGHT, PLAYER_WIDTH / 2.0),
+        }
+    }
+
+    // moves the player one axis at a time, stopping at the hitboxes of collideable blocks, and returns how far it got
+    fn move_colliding(&mut self, movement: Vector3<f32>, chunk_map: &ChunkMap) -> Vector3<f32> {
+        let origin: GlobalVecU = self.position.into();
+        let mut hitbox = self.local_hitbox(origin);
+        let area = LocalBoundingBox {
+            start: hitbox.start + movement.map(|f| f.min(0.0)),
+            end: hitbox.end + movement.map(|f| f.max(0.0)),
+        };
+        let block_hitboxes = collision_hitboxes(chunk_map, origin, &area);
+
+        let mut moved = Vector3::zero();
+        // vertical first so walking off an edge doesn't catch on the block below
+        for axis in [1, 0, 2] {
+            moved[axis] = block_hitboxes.iter().fold(movement[axis], |movement, block_hitbox| hitbox.clip_movement(block_hitbox, axis, movement));
+            let mut offset = Vector3::zero();
+            offset[axis] = moved[axis];
+            hitbox = hitbox.offset(offset);
         }
 
-        if vertical_movement_vector.magnitude2() > 0.0 {
-            self.position += vertical_movement_vector * speed;
+        self.position += moved;
+        moved
+    }
+
+    pub fn raycast_block(&self, chunk_manager: &ChunkManager) -> Option<BlockRaycastResult> {
+        let ray = Ray::new(self.position, self.direction, PLAYER_REACH);
+        raycast_block(&chunk_manager.chunk_map_lock.read(), &ray)
+    }
+
+    pub fn handle_mouse_scroll(&mut self, delta: f32) {
+        self.scroll_remainder += delta;
+        let steps = self.scroll_remainder.trunc();
+        self.scroll_remainder -= steps;
+        if steps != 0.0 {
+            self.hotbar.scroll(steps as i32);
         }
     }
 
-    pub fn modify_block(&mut self, chunk_manager: &mut ChunkManager, block: Block) {
+    // copies the targeted block with its state into the hotbar
+    pub fn pick_block(&mut self, chunk_manager: &ChunkManager) {
+        let Some(raycast_result) = self.raycast_block(chunk_manager) else { return; };
+        self.hotbar.pick(&raycast_result.block);
+    }
+
+    // wears down the targeted block by the time since the last update, the block breaks once it's been mined for its hardness in seconds
+    fn mine_block(&mut self, chunk_manager: &mut ChunkManager) {
+        let dt = self.last_mining_update.elapsed().as_secs_f32();
+        self.last_mining_update = std::time::Instant::now();
+
+        let Some(raycast_result) = self.raycast_block(chunk_manager) else {
+            self.mining = None;
+            return;
+        };
+        let position = raycast_result.block_position;
+        let mut mining = match self.mining {
+            Some(mining) if mining.position == position => mining,
+            _ => MiningProgress { position, progress: 0.0 },
+        };
+
+        let hardness = raycast_result.block.properties().hardness;
+        mining.progress += if hardness > 0.0 { dt / hardness } else { 1.0 };
+        if mining.progress < 1.0 {
+            self.mining = Some(mining);
+            return;
+        }
+
+        self.mining = None;
+        let air = assets().block_map.get("air").unwrap().clone().into();
+        chunk_manager.set_block(position, air);
+    }
+
+    // mines the targeted block or places the hotbar's selected block against it, in creative mode or with instant break blocks break on click
+    pub fn modify_block(&mut self, chunk_manager: &mut ChunkManager) {
+        if !self.game_mode.can_modify_blocks() { return; }
+        if self.is_left_mouse_pressed && !(self.instant_break || self.game_mode.breaks_instantly()) {
+            self.mine_block(chunk_manager);
+            return;
+        }
+
         if self.last_block_modification.elapsed().as_nanos() == 0 { return; }
         self.last_block_modification = std::time::Instant::now() + std::time::Duration::from_millis(200);
         
-        let mut collided_face = None;
-        let mut collision_pos = None;
-        
-        for voxel_pos in self.position.interpolate_voxels(self.direction, 5.0) {
-            let chunk_map = chunk_manager.chunk_map_lock.read();
-            let Some(block) = chunk_map.get_block(voxel_pos) else { continue; };
-            // let block_info = BLOCK_LIST.get(block.id()).unwrap();
-            if !block.properties().targetable { continue; }
-            let Some(variants) = BLOCK_MODEL_VARIANTS.get_model_variants(block) else { continue; };
-            drop(chunk_map);
-
-            let ray = Ray::new(self.position, self.direction, 5.0);
-            let mut nearest_collision = None;
-            for variant in variants {
-                for bounding_box in variant.hitboxes.iter() {
-                    let global_bounding_box = GlobalBoundingBox {
-                        start: voxel_pos + bounding_box.start,
-                        end: voxel_pos + bounding_box.end,
-                    };
-
-                    if let Some((face, t)) = global_bounding_box.ray_intersection_block_face_time(&ray) {
-                        match nearest_collision {
-                            Some((face, min_t)) => {
-                                if t < min_t {
-                                    nearest_collision = Some((face, min_t));
-                                }
-                            },
-                            None => {
-                                nearest_collision = Some((face, t));
-                            }
-                        }
-                    }
-                }
-            }
-            if let Some((face, _)) = nearest_collision {
-                collided_face = Some(face);
-                collision_pos = Some(voxel_pos);
-                break;
-            }
-        }
-
-        let Some(face) = collided_face else { return; };
-        let mut voxel_pos = collision_pos.unwrap();
+        let Some(raycast_result) = self.raycast_block(chunk_manager) else { return; };
+        let face = raycast_result.face;
+        let mut voxel_pos = raycast_result.block_position;
 
-        let air = BLOCK_MAP.get("air").unwrap().clone().into();
+        let air = assets().block_map.get("air").unwrap().clone().into();
         if self.is_left_mouse_pressed {
-            chunk_manager.chunk_map_lock.write().set_block(voxel_pos, air);
+            chunk_manager.set_block(voxel_pos, air);
         } else if self.is_right_mouse_pressed {
             voxel_pos += face.normal_i32();
-            let mut chunk_map = chunk_manager.chunk_map_lock.write();
             {
+                let chunk_map = chunk_manager.chunk_map_lock.read();
                 let Some(block) = chunk_map.get_block(voxel_pos) else { return; };
                 if !block.properties().replaceable { return; }
             }
 
-            chunk_map.set_block(voxel_pos, block);
-        } else {
-            return;
+            let Some(item) = self.hotbar.selected_item() else { return; };
+            let Some(mut block) = item.block() else { return; };
+            // picked blocks are placed exactly as they were
+            if item.block_state.is_none() {
+                block.apply_placement(&BlockPlacementContext {
+                    clicked_face: face,
+                    hit_point: (raycast_result.hit_point - voxel_pos).into(),
+                    player_direction: self.direction,
+                    sneaking: self.is_down_pressed,
+                });
+            }
+            // the player would get stuck in the block
+            if self.game_mode.collides_with_blocks() && block.properties().collideable {
+                let player_hitbox = self.local_hitbox(voxel_pos);
+                if block_local_hitboxes(&block).iter().any(|hitbox| hitbox.intersects(&player_hitbox)) { return; }
+            }
+            chunk_manager.set_block(voxel_pos, block);
+        }
+    }
+}
+
+// the hitboxes of the collideable blocks in the area, relative to the origin,
+// chunks that aren't fully generated are solid so the player can't fall through the world while it loads
+fn collision_hitboxes(chunk_map: &ChunkMap, origin: GlobalVecU, area: &LocalBoundingBox) -> Vec<LocalBoundingBox> {
+    let start = area.start.map(|f| f.floor() as i32);
+    let end = area.end.map(|f| f.ceil() as i32);
+    let mut hitboxes = vec![];
+    for x in start.x..end.x {
+        for y in start.y..end.y {
+            for z in start.z..end.z {
+                let offset = Vector3::new(x, y, z);
+                let position = origin + offset;
+                let block_offset = offset.map(|f| f as f32);
+                let is_generated = chunk_map.borrow_chunk(&position.chunk.xz())
+                    .is_some_and(|chunk| chunk.generation_stage as u8 >= GenerationStage::LAST_GENERATION_STAGE as u8);
+                if !is_generated {
+                    let height = position.chunk.y * CHUNK_SIZE as i32 + position.local().y as i32;
+                    if (0..CHUNK_HEIGHT as i32).contains(&height) {
+                        hitboxes.push(LocalBoundingBox::default().offset(block_offset));
+                    }
+                    continue;
+                }
+
+                let Some(block) = chunk_map.get_block(position) else { continue; };
+                if !block.properties().collideable { continue; }
+                hitboxes.extend(block_local_hitboxes(block).into_iter().map(|hitbox| hitbox.offset(block_offset)));
+            }
         }
-        
-        chunk_manager.changed_blocks.push(voxel_pos);
     }
+    hitboxes
 }
 
 impl Camera for Player {
@@ -219,8 +466,9 @@ impl Camera for Player {
         0.1   
     }
 
+    // far enough to reach the corners of the loaded area
     fn z_far(&self) -> f32 {
-        100.0
+        (self.render_distance + 1) as f32 * CHUNK_SIZE_F32 * std::f32::consts::SQRT_2
     }
 
     fn camera_direction(&self) -> Vector3<f32> {
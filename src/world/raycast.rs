@@ -0,0 +1,63 @@
+use crate::{block::{Block, FaceDirection}, collision::bounding_box::{GlobalBoundingBox, LocalBoundingBox, Ray}, global_vector::{GlobalVecF, GlobalVecU}, assets};
+
+use super::chunk::chunk_map::ChunkMap;
+
+#[derive(Debug, Clone)]
+pub struct BlockRaycastResult {
+    pub block: Block,
+    pub block_position: GlobalVecU,
+    pub face: FaceDirection,
+    pub hit_point: GlobalVecF,
+}
+
+impl BlockRaycastResult {
+    pub fn hitboxes(&self) -> Vec<GlobalBoundingBox> {
+        block_hitboxes(&self.block, self.block_position)
+    }
+}
+
+pub fn block_hitboxes(block: &Block, block_position: GlobalVecU) -> Vec<GlobalBoundingBox> {
+    let assets = assets();
+    let Some(variants) = assets.block_model_variants.get_model_variants(block) else { return vec![]; };
+    variants.iter()
+        .flat_map(|variant| variant.hitboxes.iter())
+        .map(|bounding_box| GlobalBoundingBox {
+            start: block_position + bounding_box.start,
+            end: block_position + bounding_box.end,
+        })
+        .collect()
+}
+
+pub fn block_local_hitboxes(block: &Block) -> Vec<LocalBoundingBox> {
+    let assets = assets();
+    let Some(variants) = assets.block_model_variants.get_model_variants(block) else { return vec![]; };
+    variants.iter().flat_map(|variant| variant.hitboxes.iter().copied()).collect()
+}
+
+// returns the first targetable block whose hitboxes are hit by the ray
+pub fn raycast_block(chunk_map: &ChunkMap, ray: &Ray) -> Option<BlockRaycastResult> {
+    for voxel_pos in ray.origin.interpolate_voxels(ray.direction, ray.length) {
+        let Some(block) = chunk_map.get_block(voxel_pos) else { continue; };
+        if !block.properties().targetable { continue; }
+
+        let mut nearest_collision: Option<(FaceDirection, f32)> = None;
+        for hitbox in block_hitboxes(block, voxel_pos) {
+            let Some((face, t)) = hitbox.ray_intersection_block_face_time(ray) else { continue; };
+            if t > ray.length { continue; }
+            if nearest_collision.map_or(true, |(_, min_t)| t < min_t) {
+                nearest_collision = Some((face, t));
+            }
+        }
+
+        if let Some((face, t)) = nearest_collision {
+            return Some(BlockRaycastResult {
+                block: block.clone(),
+                block_position: voxel_pos,
+                face,
+                hit_point: ray.origin + ray.direction * t,
+            });
+        }
+    }
+
+    None
+}
@@ -23,9 +23,13 @@ impl Region {
     pub fn save<P: AsRef<std::path::Path>>(&self, directory: P) -> anyhow::Result<()> {
         let mut path: std::path::PathBuf = directory.as_ref().to_owned();
         path.push(Self::position_to_file_name(self.position));
-        let mut file = std::fs::File::create(path)?;
+        // the old region is only replaced once the new one is written completely
+        let temporary_path = path.with_extension("tmp");
+        let mut file = std::fs::File::create(&temporary_path)?;
         let buf = rmp_serde::to_vec(self)?;
         file.write_all(&buf)?;
+        drop(file);
+        std::fs::rename(temporary_path, path)?;
         Ok(())
     }
 
@@ -44,6 +48,12 @@ impl Region {
         format!("{}_{}", position.x, position.y)
     }
 
+    // the position of the region that's saved in the file, None when it isn't a region file
+    pub fn file_name_to_position(file_name: &str) -> Option<Vector2<i32>> {
+        let (x, z) = file_name.split_once('_')?;
+        Some(Vector2::new(x.parse().ok()?, z.parse().ok()?))
+    }
+
     // pub fn are_all_chunks_unloaded(&self) -> bool {
     //     for chunk in self.chunks.values() {
 
@@ -0,0 +1,90 @@
+use std::path::Path;
+
+use cgmath::{Vector2, Vector3};
+
+use crate::global_vector::GlobalVecU;
+
+use super::{chunk::{chunk_generator::GenerationStage, chunk_map::ChunkMap, chunk_part::{CHUNK_SIZE, CHUNK_SIZE_I32}}, map::surface_block, CHUNK_HEIGHT};
+
+pub const SPAWN_SAVE_PATH: &str = "./save/spawn";
+// how many chunks away from the search's center a safe column is looked for
+const SPAWN_SEARCH_RADIUS: i32 = 2;
+
+// Where new players start, the position is the block their feet are in
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Spawn {
+    // waits for the chunks around the search's center to be generated
+    Searching,
+    Found(Vector3<i32>),
+}
+
+#[derive(serde::Deserialize, serde::Serialize)]
+struct SpawnSave {
+    position: Vector3<i32>,
+}
+
+impl Spawn {
+    #[inline]
+    pub fn position(&self) -> Option<Vector3<i32>> {
+        match self {
+            Self::Searching => None,
+            Self::Found(position) => Some(*position),
+        }
+    }
+
+    // nothing is written while it's still searching, the old spawn is only replaced once the new one is fully written
+    pub fn save<P: AsRef<Path>>(&self, path: P) -> anyhow::Result<()> {
+        let Some(position) = self.position() else { return Ok(()); };
+        if let Some(directory) = path.as_ref().parent() {
+            std::fs::create_dir_all(directory)?;
+        }
+        let temporary_path = path.as_ref().with_extension("tmp");
+        std::fs::write(&temporary_path, rmp_serde::to_vec(&SpawnSave { position })?)?;
+        std::fs::rename(temporary_path, path)?;
+        Ok(())
+    }
+
+    pub fn load<P: AsRef<Path>>(path: P) -> anyhow::Result<Self> {
+        let spawn_save: SpawnSave = rmp_serde::from_slice(&std::fs::read(path)?)?;
+        Ok(Self::Found(spawn_save.position))
+    }
+
+    // looks at the chunks around the center chunk from the closest one and returns the first column whose highest block
+    // can be stood on with two blocks of air above it, None while the next chunk to look at isn't fully generated.
+    // when no column is safe the player is put on top of the highest column that has two blocks of air above it,
+    // e.g. on water. None when every column is only air so it keeps searching
+    pub fn search(chunk_map: &ChunkMap, center: Vector2<i32>) -> Option<Vector3<i32>> {
+        let mut chunk_offsets = (-SPAWN_SEARCH_RADIUS..=SPAWN_SEARCH_RADIUS)
+            .flat_map(|z| (-SPAWN_SEARCH_RADIUS..=SPAWN_SEARCH_RADIUS).map(move |x| Vector2::new(x, z)))
+            .collect::<Vec<Vector2<i32>>>();
+        chunk_offsets.sort_by_key(|offset| offset.x * offset.x + offset.y * offset.y);
+        let center_origin = center * CHUNK_SIZE_I32;
+
+        let mut highest_feet: Option<Vector3<i32>> = None;
+        let mut columns = (0..CHUNK_SIZE as u8).flat_map(|z| (0..CHUNK_SIZE as u8).map(move |x| Vector2::new(x, z))).collect::<Vec<Vector2<u8>>>();
+        for chunk_offset in chunk_offsets {
+            let chunk_position = center + chunk_offset;
+            let chunk = chunk_map.borrow_chunk(&chunk_position).filter(|chunk| chunk.generation_stage == GenerationStage::LAST_GENERATION_STAGE)?;
+            let chunk_origin = chunk_position * CHUNK_SIZE_I32;
+            // the columns closest to the center chunk's first column first
+            columns.sort_by_key(|column| {
+                let offset = chunk_origin + column.map(|f| f as i32) - center_origin;
+                offset.x * offset.x + offset.y * offset.y
+            });
+            for column in columns.iter() {
+                let Some((height, block)) = surface_block(chunk, *column) else { continue; };
+                if height as usize + 2 >= CHUNK_HEIGHT { continue; }
+                let feet = Vector3::new(chunk_origin.x + column.x as i32, height as i32 + 1, chunk_origin.y + column.y as i32);
+                let is_air = |position: Vector3<i32>| chunk_map.get_block(GlobalVecU::from(position)).is_some_and(|block| block.is_air());
+                if !is_air(feet) || !is_air(feet + Vector3::new(0, 1, 0)) { continue; }
+                if block.properties().collideable && !block.properties().fluid {
+                    return Some(feet);
+                }
+                if highest_feet.is_none_or(|highest_feet| feet.y > highest_feet.y) {
+                    highest_feet = Some(feet);
+                }
+            }
+        }
+        highest_feet
+    }
+}
@@ -1,7 +1,18 @@
+use std::path::Path;
+
 use cgmath::Vector3;
+use schematic::Schematic;
+
+use crate::{block::{block_state::Value, Block}, assets};
 
-use crate::block::Block;
+pub mod schematic;
+pub mod sponge_schematic;
+pub mod vox;
+mod nbt;
 
+pub const STRUCTURES_DIRECTORY: &str = "./save/structures";
+
+#[derive(Debug, Clone, Default)]
 pub struct Structure {
     pub blocks: Vec<(Vector3<i32>, Block)>,
 }
@@ -10,4 +21,60 @@ impl Structure {
     pub fn blocks(&self) -> &[(Vector3<i32>, Block)] {
         &self.blocks
     }
-}
\ No newline at end of file
+
+    // writes a structure file or exports a .vox model by the extension
+    pub fn save<P: AsRef<Path>>(&self, path: P) -> anyhow::Result<()> {
+        match path.as_ref().extension().and_then(|extension| extension.to_str()) {
+            Some(vox::VOX_EXTENSION) => vox::save(self, path),
+            _ => Schematic::from_structure(self)?.save(path),
+        }
+    }
+
+    // loads a structure file or imports a sponge .schem file or a .vox model by the extension,
+    // blocks that don't exist are left out and their names are returned with the structure
+    pub fn load<P: AsRef<Path>>(path: P) -> anyhow::Result<(Self, Vec<String>)> {
+        match path.as_ref().extension().and_then(|extension| extension.to_str()) {
+            Some(sponge_schematic::SPONGE_SCHEMATIC_EXTENSION) => sponge_schematic::load(path),
+            Some(vox::VOX_EXTENSION) => vox::load(path),
+            _ => Ok(Schematic::load(path)?.to_structure()),
+        }
+    }
+}
+
+// the block with the name and the given states, states the block doesn't have are ignored
+pub fn block_from_name(name: &str, states: impl IntoIterator<Item = (String, Value)>) -> Option<Block> {
+    let assets = assets();
+    let block_info = assets.block_map.get(name)?;
+    let mut block: Block = block_info.clone().into();
+    let mut has_changed = false;
+    for (state_name, value) in states {
+        if block_info.default_state().get(&state_name).is_none() { continue; }
+        block.block_state.insert(state_name, value);
+        has_changed = true;
+    }
+    if has_changed {
+        block.refresh_properties();
+    }
+    Some(block)
+}
+
+// e.g. "oak_stairs[facing=north,half=bottom]", a namespace in front of the name like "minecraft:" is left out
+pub fn block_from_key(key: &str) -> Option<Block> {
+    let (name, states) = match key.split_once('[') {
+        Some((name, states)) => (name, states.strip_suffix(']').unwrap_or(states)),
+        None => (key, ""),
+    };
+    let name = name.rsplit_once(':').map_or(name, |(_, name)| name);
+    let states = states.split(',')
+        .filter_map(|state| state.split_once('='))
+        .map(|(state_name, value)| (state_name.to_string(), Value::parse(value)));
+    block_from_name(name, states)
+}
+
+// the key block_from_key reads, the states are sorted by name
+pub fn block_key(block: &Block) -> String {
+    let mut states = block.block_state.iter().map(|(name, value)| format!("{}={}", name, value)).collect::<Vec<String>>();
+    if states.is_empty() { return block.name().to_string(); }
+    states.sort();
+    format!("{}[{}]", block.name(), states.join(","))
+}
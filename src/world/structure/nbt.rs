@@ -0,0 +1,173 @@
+use hashbrown::HashMap;
+
+// how deep lists and compounds can be nested, the same limit as Minecraft's
+const MAX_DEPTH: usize = 512;
+
+// The tags of Minecraft's named binary tag format that the schematic importer reads,
+// the other tags are parsed and skipped so they're left out of their compounds
+#[derive(Debug, Clone)]
+pub enum Tag {
+    Byte(i8),
+    Short(i16),
+    Int(i32),
+    Long(i64),
+    ByteArray(Vec<i8>),
+    Compound(HashMap<String, Tag>),
+}
+
+impl Tag {
+    pub fn get(&self, name: &str) -> Option<&Tag> {
+        match self {
+            Self::Compound(tags) => tags.get(name),
+            _ => None,
+        }
+    }
+
+    pub fn as_compound(&self) -> Option<&HashMap<String, Tag>> {
+        match self {
+            Self::Compound(tags) => Some(tags),
+            _ => None,
+        }
+    }
+
+    // any of the integer tags
+    pub fn as_i64(&self) -> Option<i64> {
+        match self {
+            Self::Byte(value) => Some(*value as i64),
+            Self::Short(value) => Some(*value as i64),
+            Self::Int(value) => Some(*value as i64),
+            Self::Long(value) => Some(*value),
+            _ => None,
+        }
+    }
+
+    pub fn as_byte_array(&self) -> Option<&[i8]> {
+        match self {
+            Self::ByteArray(bytes) => Some(bytes),
+            _ => None,
+        }
+    }
+}
+
+struct Reader<'a> {
+    bytes: &'a [u8],
+}
+
+impl<'a> Reader<'a> {
+    fn take<const N: usize>(&mut self) -> anyhow::Result<[u8; N]> {
+        let (taken, rest) = self.bytes.split_first_chunk::<N>().ok_or_else(|| anyhow::anyhow!("the nbt data ends too early"))?;
+        self.bytes = rest;
+        Ok(*taken)
+    }
+
+    fn length(&mut self) -> anyhow::Result<usize> {
+        let length = i32::from_be_bytes(self.take()?);
+        if length < 0 { anyhow::bail!("the nbt data has a negative length"); }
+        Ok(length as usize)
+    }
+
+    fn advance(&mut self, length: usize) -> anyhow::Result<()> {
+        if self.bytes.len() < length { anyhow::bail!("the nbt data ends too early"); }
+        self.bytes = &self.bytes[length..];
+        Ok(())
+    }
+
+    fn string(&mut self) -> anyhow::Result<String> {
+        let length = u16::from_be_bytes(self.take()?) as usize;
+        if self.bytes.len() < length { anyhow::bail!("the nbt data ends too early"); }
+        let (string, rest) = self.bytes.split_at(length);
+        self.bytes = rest;
+        // the strings are java's modified utf-8, which only differs for nul and characters outside the basic plane
+        Ok(String::from_utf8_lossy(string).into_owned())
+    }
+
+    fn array<T>(&mut self, mut read: impl FnMut(&mut Self) -> anyhow::Result<T>) -> anyhow::Result<Vec<T>> {
+        let length = self.length()?;
+        // the length isn't trusted to reserve memory, every element is at least a byte
+        let mut elements = Vec::with_capacity(length.min(self.bytes.len()));
+        for _ in 0..length {
+            elements.push(read(self)?);
+        }
+        Ok(elements)
+    }
+
+    // None when the tag is one that's skipped, depth is how many lists and compounds the tag is in
+    fn payload(&mut self, tag_id: u8, depth: usize) -> anyhow::Result<Option<Tag>> {
+        if depth > MAX_DEPTH { anyhow::bail!("the nbt data is nested deeper than {} tags", MAX_DEPTH); }
+        Ok(Some(match tag_id {
+            1 => Tag::Byte(i8::from_be_bytes(self.take()?)),
+            2 => Tag::Short(i16::from_be_bytes(self.take()?)),
+            3 => Tag::Int(i32::from_be_bytes(self.take()?)),
+            4 => Tag::Long(i64::from_be_bytes(self.take()?)),
+            7 => Tag::ByteArray(self.array(|reader| Ok(i8::from_be_bytes(reader.take()?)))?),
+            10 => {
+                let mut tags = HashMap::new();
+                loop {
+                    let tag_id = u8::from_be_bytes(self.take()?);
+                    if tag_id == 0 { break; }
+                    let name = self.string()?;
+                    if let Some(tag) = self.payload(tag_id, depth + 1)? {
+                        tags.insert(name, tag);
+                    }
+                }
+                Tag::Compound(tags)
+            },
+            _ => {
+                self.skip(tag_id, depth)?;
+                return Ok(None);
+            },
+        }))
+    }
+
+    // moves past the payload without keeping it
+    fn skip(&mut self, tag_id: u8, depth: usize) -> anyhow::Result<()> {
+        if depth > MAX_DEPTH { anyhow::bail!("the nbt data is nested deeper than {} tags", MAX_DEPTH); }
+        match tag_id {
+            1 => self.advance(1),
+            2 => self.advance(2),
+            // int and float
+            3 | 5 => self.advance(4),
+            // long and double
+            4 | 6 => self.advance(8),
+            7 => {
+                let length = self.length()?;
+                self.advance(length)
+            },
+            8 => {
+                let length = u16::from_be_bytes(self.take()?) as usize;
+                self.advance(length)
+            },
+            9 => {
+                let element_id = u8::from_be_bytes(self.take()?);
+                for _ in 0..self.length()? {
+                    self.skip(element_id, depth + 1)?;
+                }
+                Ok(())
+            },
+            10 => loop {
+                let tag_id = u8::from_be_bytes(self.take()?);
+                if tag_id == 0 { return Ok(()); }
+                let name_length = u16::from_be_bytes(self.take()?) as usize;
+                self.advance(name_length)?;
+                self.skip(tag_id, depth + 1)?;
+            },
+            // int and long arrays
+            11 | 12 => {
+                let element_size = if tag_id == 11 { 4 } else { 8 };
+                let length = self.length()?;
+                self.advance(length.checked_mul(element_size).ok_or_else(|| anyhow::anyhow!("the nbt data ends too early"))?)
+            },
+            _ => anyhow::bail!("unknown nbt tag {}", tag_id),
+        }
+    }
+}
+
+// reads the uncompressed data's root tag and its name
+pub fn read(bytes: &[u8]) -> anyhow::Result<(String, Tag)> {
+    let mut reader = Reader { bytes };
+    let tag_id = u8::from_be_bytes(reader.take()?);
+    if tag_id != 10 { anyhow::bail!("the nbt data doesn't start with a compound tag"); }
+    let name = reader.string()?;
+    let root = reader.payload(tag_id, 0)?.ok_or_else(|| anyhow::anyhow!("the nbt data doesn't start with a compound tag"))?;
+    Ok((name, root))
+}
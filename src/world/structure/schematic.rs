@@ -0,0 +1,114 @@
+use std::{io::{Read, Write}, path::Path};
+
+use cgmath::Vector3;
+use flate2::{read::GzDecoder, write::GzEncoder, Compression};
+
+use crate::block::{block_state::BlockState, Block};
+
+use super::{block_from_name, Structure};
+
+pub const STRUCTURE_EXTENSION: &str = "structure";
+// where the structure doesn't set a block
+const NO_BLOCK: u16 = u16::MAX;
+
+// A block of the palette, it's stored by name so the file doesn't depend on the block ids
+#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
+pub struct SchematicBlock {
+    pub name: String,
+    pub block_state: BlockState,
+}
+
+// The structure as it's written to a file, every position of the box around the structure is an index into the palette
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+pub struct Schematic {
+    // the box's lowest corner relative to the structure's origin
+    pub offset: Vector3<i32>,
+    pub size: Vector3<u32>,
+    pub palette: Vec<SchematicBlock>,
+    // x first, then z, then y
+    pub blocks: Vec<u16>,
+}
+
+impl Schematic {
+    pub fn from_structure(structure: &Structure) -> anyhow::Result<Self> {
+        let Some((first_position, _)) = structure.blocks().first() else {
+            return Ok(Self { offset: Vector3::new(0, 0, 0), size: Vector3::new(0, 0, 0), palette: vec![], blocks: vec![] });
+        };
+        let (min, max) = structure.blocks().iter().fold((*first_position, *first_position), |(min, max), (position, _)| {
+            (min.zip(*position, i32::min), max.zip(*position, i32::max))
+        });
+        let size = (max - min).map(|f| f as u32 + 1);
+
+        let mut palette: Vec<Block> = vec![];
+        let mut blocks = vec![NO_BLOCK; size.x as usize * size.y as usize * size.z as usize];
+        for (position, block) in structure.blocks() {
+            let palette_index = match palette.iter().rposition(|f| f == block) {
+                Some(palette_index) => palette_index,
+                None => {
+                    palette.push(block.clone());
+                    palette.len() - 1
+                },
+            };
+            if palette_index >= NO_BLOCK as usize {
+                anyhow::bail!("the structure has more than {} different blocks", NO_BLOCK);
+            }
+            blocks[Self::index(size, (position - min).map(|f| f as u32))] = palette_index as u16;
+        }
+
+        Ok(Self {
+            offset: min,
+            size,
+            palette: palette.into_iter().map(|block| SchematicBlock { name: block.name().to_string(), block_state: block.block_state }).collect(),
+            blocks,
+        })
+    }
+
+    #[inline]
+    fn index(size: Vector3<u32>, position: Vector3<u32>) -> usize {
+        position.x as usize + (position.z as usize + position.y as usize * size.z as usize) * size.x as usize
+    }
+
+    // blocks that don't exist anymore are left out, their names are returned with the structure
+    pub fn to_structure(&self) -> (Structure, Vec<String>) {
+        let palette = self.palette.iter()
+            .map(|schematic_block| block_from_name(&schematic_block.name, schematic_block.block_state.iter().map(|(name, value)| (name.clone(), value.clone()))))
+            .collect::<Vec<Option<Block>>>();
+        let mut unknown_blocks = self.palette.iter().zip(palette.iter())
+            .filter(|(_, block)| block.is_none())
+            .map(|(schematic_block, _)| schematic_block.name.clone())
+            .collect::<Vec<String>>();
+        unknown_blocks.sort();
+        unknown_blocks.dedup();
+
+        let mut structure = Structure::default();
+        for y in 0..self.size.y {
+            for z in 0..self.size.z {
+                for x in 0..self.size.x {
+                    let position = Vector3::new(x, y, z);
+                    let Some(palette_index) = self.blocks.get(Self::index(self.size, position)) else { continue; };
+                    let Some(Some(block)) = palette.get(*palette_index as usize) else { continue; };
+                    structure.blocks.push((self.offset + position.map(|f| f as i32), block.clone()));
+                }
+            }
+        }
+        (structure, unknown_blocks)
+    }
+
+    pub fn save<P: AsRef<Path>>(&self, path: P) -> anyhow::Result<()> {
+        let file = std::fs::File::create(path)?;
+        let mut encoder = GzEncoder::new(file, Compression::default());
+        encoder.write_all(&rmp_serde::to_vec(self)?)?;
+        encoder.finish()?;
+        Ok(())
+    }
+
+    pub fn load<P: AsRef<Path>>(path: P) -> anyhow::Result<Self> {
+        let mut bytes = vec![];
+        GzDecoder::new(std::fs::File::open(path)?).read_to_end(&mut bytes)?;
+        let schematic: Self = rmp_serde::from_slice(&bytes)?;
+        if schematic.blocks.len() != schematic.size.x as usize * schematic.size.y as usize * schematic.size.z as usize {
+            anyhow::bail!("the structure has {} blocks but its size is {}x{}x{}", schematic.blocks.len(), schematic.size.x, schematic.size.y, schematic.size.z);
+        }
+        Ok(schematic)
+    }
+}
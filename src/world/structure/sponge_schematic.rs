@@ -0,0 +1,73 @@
+use std::{io::Read, path::Path};
+
+use cgmath::Vector3;
+use flate2::read::GzDecoder;
+
+use crate::block::Block;
+
+use super::{block_from_key, nbt::{self, Tag}, Structure};
+
+pub const SPONGE_SCHEMATIC_EXTENSION: &str = "schem";
+
+// imports a sponge schematic of any version, the blocks are mapped by name with the namespace left out,
+// blocks that don't exist here are left out and their names are returned with the structure
+pub fn load<P: AsRef<Path>>(path: P) -> anyhow::Result<(Structure, Vec<String>)> {
+    let mut bytes = vec![];
+    GzDecoder::new(std::fs::File::open(path)?).read_to_end(&mut bytes)?;
+    let (_, root) = nbt::read(&bytes)?;
+    // version 3 puts everything into a compound called Schematic
+    let schematic = root.get("Schematic").unwrap_or(&root);
+
+    let dimension = |name: &str| schematic.get(name).and_then(Tag::as_i64).map(|f| f as u16 as u32).ok_or_else(|| anyhow::anyhow!("the schematic has no {}", name));
+    let size = Vector3::new(dimension("Width")?, dimension("Height")?, dimension("Length")?);
+    if size.x == 0 || size.y == 0 || size.z == 0 { return Ok((Structure::default(), vec![])); }
+
+    // version 3 keeps the palette and the data in a compound called Blocks
+    let (palette, data) = match schematic.get("Blocks") {
+        Some(blocks) => (blocks.get("Palette"), blocks.get("Data")),
+        None => (schematic.get("Palette"), schematic.get("BlockData")),
+    };
+    let palette = palette.and_then(Tag::as_compound).ok_or_else(|| anyhow::anyhow!("the schematic has no block palette"))?;
+    let data = data.and_then(Tag::as_byte_array).ok_or_else(|| anyhow::anyhow!("the schematic has no block data"))?;
+
+    let mut unknown_blocks = vec![];
+    let mut blocks: Vec<Option<Block>> = vec![];
+    for (key, index) in palette.iter() {
+        let index = index.as_i64().filter(|index| (0..=u16::MAX as i64).contains(index)).ok_or_else(|| anyhow::anyhow!("{} has no palette index", key))? as usize;
+        if blocks.len() <= index {
+            blocks.resize(index + 1, None);
+        }
+        blocks[index] = block_from_key(key);
+        if blocks[index].is_none() {
+            unknown_blocks.push(key.split('[').next().unwrap_or(key).to_string());
+        }
+    }
+    unknown_blocks.sort();
+    unknown_blocks.dedup();
+
+    let mut structure = Structure::default();
+    let mut bytes = data.iter().map(|byte| *byte as u8);
+    let mut index = 0;
+    while let Some(palette_index) = read_varint(&mut bytes)? {
+        let position = Vector3::new(index % size.x, index / (size.x * size.z), (index / size.x) % size.z);
+        index += 1;
+        if position.y >= size.y { anyhow::bail!("the schematic has more blocks than fit into {}x{}x{}", size.x, size.y, size.z); }
+        let Some(Some(block)) = blocks.get(palette_index as usize) else { continue; };
+        structure.blocks.push((position.map(|f| f as i32), block.clone()));
+    }
+    Ok((structure, unknown_blocks))
+}
+
+// the palette indices are stored as varints, None when the bytes have ended
+fn read_varint(bytes: &mut impl Iterator<Item = u8>) -> anyhow::Result<Option<u32>> {
+    let mut value = 0;
+    for shift in (0..32).step_by(7) {
+        let Some(byte) = bytes.next() else {
+            if shift == 0 { return Ok(None); }
+            anyhow::bail!("the block data ends inside of a varint");
+        };
+        value |= ((byte & 0x7f) as u32) << shift;
+        if byte & 0x80 == 0 { return Ok(Some(value)); }
+    }
+    anyhow::bail!("the block data has a varint that's too long")
+}
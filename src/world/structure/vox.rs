@@ -0,0 +1,180 @@
+use std::{collections::BTreeMap, io::Write, path::{Path, PathBuf}};
+
+use cgmath::Vector3;
+
+use crate::block::Block;
+
+use super::{block_from_key, block_key, Structure};
+
+pub const VOX_EXTENSION: &str = "vox";
+const VOX_VERSION: i32 = 150;
+// a model's size is stored in bytes
+const MAX_MODEL_SIZE: i32 = 256;
+// index 0 means empty
+const MAX_PALETTE_INDICES: usize = 255;
+
+// Which block every palette index of a .vox file stands for, it's kept as json next to the model
+// e.g. { "1": "stone", "2": "oak_log[axis=y]" }
+pub type VoxMapping = BTreeMap<u8, String>;
+
+// the mapping file that belongs to the model
+pub fn mapping_path<P: AsRef<Path>>(path: P) -> PathBuf {
+    path.as_ref().with_extension("json")
+}
+
+struct Reader<'a> {
+    bytes: &'a [u8],
+}
+
+impl<'a> Reader<'a> {
+    fn take<const N: usize>(&mut self) -> anyhow::Result<[u8; N]> {
+        let (taken, rest) = self.bytes.split_first_chunk::<N>().ok_or_else(|| anyhow::anyhow!("the .vox file ends too early"))?;
+        self.bytes = rest;
+        Ok(*taken)
+    }
+
+    fn i32(&mut self) -> anyhow::Result<i32> {
+        Ok(i32::from_le_bytes(self.take()?))
+    }
+
+    fn skip(&mut self, length: usize) -> anyhow::Result<&'a [u8]> {
+        if self.bytes.len() < length { anyhow::bail!("the .vox file ends too early"); }
+        let (skipped, rest) = self.bytes.split_at(length);
+        self.bytes = rest;
+        Ok(skipped)
+    }
+}
+
+// imports the first model of the .vox file with the mapping file next to it, MagicaVoxel's z is up so the model is rotated around x
+// to make it y, its y becomes -z.
+// the scene's transforms aren't read, the model's lowest corner is the structure's origin.
+// voxels whose palette index isn't mapped to an existing block are left out, the indices and names are returned with the structure
+pub fn load<P: AsRef<Path>>(path: P) -> anyhow::Result<(Structure, Vec<String>)> {
+    let mapping_path = mapping_path(&path);
+    let mapping: VoxMapping = serde_json::from_slice(&std::fs::read(&mapping_path).map_err(|err| anyhow::anyhow!("couldn't read the mapping {}: {}", mapping_path.display(), err))?)?;
+    let bytes = std::fs::read(&path)?;
+
+    let mut reader = Reader { bytes: &bytes };
+    if &reader.take::<4>()? != b"VOX " { anyhow::bail!("the file isn't a .vox file"); }
+    reader.i32()?;
+    if &reader.take::<4>()? != b"MAIN" { anyhow::bail!("the .vox file has no MAIN chunk"); }
+    let main_content_size = reader.i32()?;
+    reader.i32()?;
+    reader.skip(main_content_size.max(0) as usize)?;
+
+    let mut size_y = None;
+    let mut voxels = None;
+    while !reader.bytes.is_empty() {
+        let id = reader.take::<4>()?;
+        let content_size = reader.i32()?.max(0) as usize;
+        let children_size = reader.i32()?.max(0) as usize;
+        let mut content = Reader { bytes: reader.skip(content_size)? };
+        reader.skip(children_size)?;
+        // every model's SIZE comes right before its XYZI
+        if &id == b"SIZE" && voxels.is_none() {
+            content.i32()?;
+            size_y = Some(content.i32()?);
+        }
+        if &id != b"XYZI" || voxels.is_some() { continue; }
+        let size_y = size_y.ok_or_else(|| anyhow::anyhow!("the .vox file's model has no size"))?;
+
+        let voxel_count = content.i32()?.max(0) as usize;
+        let mut model_voxels = Vec::with_capacity(voxel_count.min(content.bytes.len() / 4));
+        for _ in 0..voxel_count {
+            let [x, y, z, palette_index] = content.take::<4>()?;
+            model_voxels.push((Vector3::new(x as i32, z as i32, size_y - 1 - y as i32), palette_index));
+        }
+        voxels = Some(model_voxels);
+    }
+    let voxels = voxels.ok_or_else(|| anyhow::anyhow!("the .vox file has no model"))?;
+
+    let mut palette: [Option<Block>; 256] = std::array::from_fn(|_| None);
+    let mut unknown_blocks = vec![];
+    for (palette_index, key) in mapping.iter() {
+        palette[*palette_index as usize] = block_from_key(key);
+        if palette[*palette_index as usize].is_none() {
+            unknown_blocks.push(key.clone());
+        }
+    }
+
+    let mut structure = Structure::default();
+    for (position, palette_index) in voxels {
+        match &palette[palette_index as usize] {
+            Some(block) => structure.blocks.push((position, block.clone())),
+            None if !mapping.contains_key(&palette_index) => unknown_blocks.push(format!("palette index {}", palette_index)),
+            None => (),
+        }
+    }
+    unknown_blocks.sort();
+    unknown_blocks.dedup();
+    Ok((structure, unknown_blocks))
+}
+
+// exports the structure as one model with its lowest corner at the model's origin and writes the mapping next to it,
+// air isn't exported. blocks that are in the mapping that's already there keep their palette index
+pub fn save<P: AsRef<Path>>(structure: &Structure, path: P) -> anyhow::Result<()> {
+    let blocks = structure.blocks().iter().filter(|(_, block)| !block.is_air()).collect::<Vec<&(Vector3<i32>, Block)>>();
+    let Some((first_position, _)) = blocks.first() else { anyhow::bail!("there are only air blocks to export"); };
+    let (min, max) = blocks.iter().fold((*first_position, *first_position), |(min, max), (position, _)| {
+        (min.zip(*position, i32::min), max.zip(*position, i32::max))
+    });
+    let size = max - min + Vector3::new(1, 1, 1);
+    if size.x > MAX_MODEL_SIZE || size.y > MAX_MODEL_SIZE || size.z > MAX_MODEL_SIZE {
+        anyhow::bail!("the blocks span {}x{}x{}, a .vox model can be at most {} blocks wide on every axis", size.x, size.y, size.z, MAX_MODEL_SIZE);
+    }
+
+    let mapping_path = mapping_path(&path);
+    let mut mapping: VoxMapping = std::fs::read(&mapping_path).ok().and_then(|bytes| serde_json::from_slice(&bytes).ok()).unwrap_or_default();
+    let mut voxels = Vec::with_capacity(blocks.len() * 4);
+    for (position, block) in blocks {
+        let key = block_key(block);
+        let palette_index = match mapping.iter().find(|(_, mapped_key)| **mapped_key == key) {
+            Some((palette_index, _)) => *palette_index,
+            None => {
+                let palette_index = (1..=MAX_PALETTE_INDICES as u8).find(|palette_index| !mapping.contains_key(palette_index))
+                    .ok_or_else(|| anyhow::anyhow!("there are more than {} different blocks", MAX_PALETTE_INDICES))?;
+                mapping.insert(palette_index, key);
+                palette_index
+            },
+        };
+        let position = (position - min).map(|f| f as u8);
+        // the inverse of the rotation on import
+        voxels.extend_from_slice(&[position.x, (size.z - 1) as u8 - position.z, position.y, palette_index]);
+    }
+
+    let mut colors = Vec::with_capacity(256 * 4);
+    for palette_index in 1..=256_usize {
+        colors.extend_from_slice(&mapping.get(&(palette_index as u8)).map_or([0, 0, 0, 255], |key| block_color(key)));
+    }
+
+    let mut children = vec![];
+    write_chunk(&mut children, b"SIZE", &[size.x, size.z, size.y].map(|f| f.to_le_bytes()).concat());
+    write_chunk(&mut children, b"XYZI", &[((voxels.len() / 4) as i32).to_le_bytes().as_slice(), &voxels].concat());
+    write_chunk(&mut children, b"RGBA", &colors);
+
+    let mut file = std::fs::File::create(&path)?;
+    file.write_all(b"VOX ")?;
+    file.write_all(&VOX_VERSION.to_le_bytes())?;
+    file.write_all(b"MAIN")?;
+    file.write_all(&0_i32.to_le_bytes())?;
+    file.write_all(&(children.len() as i32).to_le_bytes())?;
+    file.write_all(&children)?;
+
+    std::fs::write(mapping_path, serde_json::to_string_pretty(&mapping)?)?;
+    Ok(())
+}
+
+fn write_chunk(bytes: &mut Vec<u8>, id: &[u8; 4], content: &[u8]) {
+    bytes.extend_from_slice(id);
+    bytes.extend_from_slice(&(content.len() as i32).to_le_bytes());
+    bytes.extend_from_slice(&0_i32.to_le_bytes());
+    bytes.extend_from_slice(content);
+}
+
+// a color that's the same for the block every time it's exported, so the blocks can be told apart in MagicaVoxel
+fn block_color(key: &str) -> [u8; 4] {
+    // fnv-1a
+    let hash = key.bytes().fold(0x811c9dc5_u32, |hash, byte| (hash ^ byte as u32).wrapping_mul(0x01000193));
+    let [r, g, b, _] = hash.to_le_bytes();
+    [r | 0x40, g | 0x40, b | 0x40, 255]
+}
@@ -0,0 +1,59 @@
+use cgmath::Vector3;
+use hashbrown::HashMap;
+
+use crate::{block::Block, chunk_position::ChunkPosition, global_vector::GlobalVecU, world::chunk::chunk_part::{chunk_part_position::ChunkPartPosition, CHUNK_SIZE_I32}};
+
+// Block changes grouped by the chunk part they're in, every block is kept once and the changes point at it,
+// so a batch is written with one pallet lookup per block and part
+#[derive(Debug, Clone, Default)]
+pub struct EditBatch {
+    blocks: Vec<Block>,
+    // x and z are the chunk's position and y is the part's index, like GlobalVecU::chunk
+    parts: HashMap<Vector3<i32>, Vec<(ChunkPartPosition, usize)>>,
+    len: usize,
+}
+
+impl EditBatch {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    #[inline]
+    pub fn blocks(&self) -> &[Block] {
+        &self.blocks
+    }
+
+    pub fn parts(&self) -> impl Iterator<Item = (Vector3<i32>, &[(ChunkPartPosition, usize)])> {
+        self.parts.iter().map(|(chunk_part_position, changes)| (*chunk_part_position, changes.as_slice()))
+    }
+
+    // every position should only be set once, positions above or below the world are left out
+    pub fn set(&mut self, position: GlobalVecU, block: &Block) {
+        let Ok(chunk_position) = ChunkPosition::try_from(position) else { return; };
+        let block_index = match self.blocks.iter().rposition(|f| f == block) {
+            Some(block_index) => block_index,
+            None => {
+                self.blocks.push(block.clone());
+                self.blocks.len() - 1
+            },
+        };
+        self.parts.entry(position.chunk).or_default().push((chunk_position.chunk_part_position(), block_index));
+        self.len += 1;
+    }
+}
+
+// the position of the block in the chunk part
+#[inline]
+pub fn part_block_position(chunk_part_position: Vector3<i32>, local_position: ChunkPartPosition) -> GlobalVecU {
+    GlobalVecU::from(chunk_part_position * CHUNK_SIZE_I32 + local_position.position().map(|f| f as i32))
+}
@@ -0,0 +1,153 @@
+use std::collections::VecDeque;
+
+use edit_batch::EditBatch;
+use selection::{BlockBox, Selection};
+
+use crate::{block::Block, global_vector::GlobalVecU, world::structure::Structure, assets};
+
+use super::chunk::chunk_manager::ChunkManager;
+
+pub mod edit_batch;
+pub mod selection;
+
+// bigger selections are refused, every changed block is kept in the history until it's pushed out
+pub const MAX_EDIT_VOLUME: u64 = 64 * 64 * 64;
+const MAX_UNDO_STEPS: usize = 32;
+
+// The selection and the edits made to the blocks in it, every edit is kept as the batch that undoes it
+pub struct WorldEdit {
+    pub selection: Selection,
+    undo_history: VecDeque<EditBatch>,
+    redo_history: Vec<EditBatch>,
+}
+
+impl WorldEdit {
+    pub fn new() -> Self {
+        Self {
+            selection: Selection::default(),
+            undo_history: VecDeque::new(),
+            redo_history: vec![],
+        }
+    }
+
+    pub fn selected_box(&self) -> anyhow::Result<BlockBox> {
+        let block_box = self.selection.block_box().ok_or_else(|| anyhow::anyhow!("select both corners first with pos1 and pos2"))?;
+        if block_box.volume() > MAX_EDIT_VOLUME {
+            anyhow::bail!("the selection has {} blocks, at most {} can be edited at once", block_box.volume(), MAX_EDIT_VOLUME);
+        }
+        Ok(block_box)
+    }
+
+    // writes the batch and remembers how to undo it, returns the number of blocks that changed
+    pub fn apply(&mut self, chunk_manager: &mut ChunkManager, batch: &EditBatch) -> usize {
+        let undo_batch = chunk_manager.apply_edit_batch(batch);
+        if undo_batch.is_empty() { return 0; }
+
+        let changed_blocks = undo_batch.len();
+        self.push_undo(undo_batch);
+        self.redo_history.clear();
+        changed_blocks
+    }
+
+    fn push_undo(&mut self, undo_batch: EditBatch) {
+        if self.undo_history.len() >= MAX_UNDO_STEPS {
+            self.undo_history.pop_front();
+        }
+        self.undo_history.push_back(undo_batch);
+    }
+
+    // returns the number of blocks that were changed back or None when there's nothing to undo
+    pub fn undo(&mut self, chunk_manager: &mut ChunkManager) -> Option<usize> {
+        let undo_batch = self.undo_history.pop_back()?;
+        let redo_batch = chunk_manager.apply_edit_batch(&undo_batch);
+        let changed_blocks = redo_batch.len();
+        self.redo_history.push(redo_batch);
+        Some(changed_blocks)
+    }
+
+    pub fn redo(&mut self, chunk_manager: &mut ChunkManager) -> Option<usize> {
+        let redo_batch = self.redo_history.pop()?;
+        let undo_batch = chunk_manager.apply_edit_batch(&redo_batch);
+        let changed_blocks = undo_batch.len();
+        self.push_undo(undo_batch);
+        Some(changed_blocks)
+    }
+
+    pub fn fill(&mut self, chunk_manager: &mut ChunkManager, block: &Block) -> anyhow::Result<usize> {
+        let block_box = self.selected_box()?;
+        let mut batch = EditBatch::new();
+        for position in block_box.iter() {
+            batch.set(position, block);
+        }
+        Ok(self.apply(chunk_manager, &batch))
+    }
+
+    // replaces the blocks with the name of `from` whatever their state is
+    pub fn replace(&mut self, chunk_manager: &mut ChunkManager, from: &Block, to: &Block) -> anyhow::Result<usize> {
+        let block_box = self.selected_box()?;
+        let mut batch = EditBatch::new();
+        {
+            let chunk_map = chunk_manager.chunk_map_lock.read();
+            for position in block_box.iter() {
+                if chunk_map.get_block(position).is_some_and(|block| block.name() == from.name()) {
+                    batch.set(position, to);
+                }
+            }
+        }
+        Ok(self.apply(chunk_manager, &batch))
+    }
+
+    // sets the faces of the selection to the block and empties the inside
+    pub fn hollow(&mut self, chunk_manager: &mut ChunkManager, block: &Block) -> anyhow::Result<usize> {
+        let block_box = self.selected_box()?;
+        let air: Block = assets().block_map.get("air").unwrap().clone().into();
+        let mut batch = EditBatch::new();
+        for position in block_box.iter() {
+            match block_box.is_on_surface(position) {
+                true => batch.set(position, block),
+                false => batch.set(position, &air),
+            }
+        }
+        Ok(self.apply(chunk_manager, &batch))
+    }
+
+    // copies the selection so its lowest corner is at the destination,
+    // every block is read before any is written, so the copy can overlap the selection
+    pub fn clone_selection(&mut self, chunk_manager: &mut ChunkManager, destination: GlobalVecU) -> anyhow::Result<usize> {
+        let block_box = self.selected_box()?;
+        // the offset from the selection to the destination can overflow, the offset inside the selection can't
+        let min = block_box.min().block_position();
+        let mut batch = EditBatch::new();
+        {
+            let chunk_map = chunk_manager.chunk_map_lock.read();
+            for position in block_box.iter() {
+                let Some(block) = chunk_map.get_block(position) else { continue; };
+                batch.set(destination + (position.block_position() - min), block);
+            }
+        }
+        Ok(self.apply(chunk_manager, &batch))
+    }
+
+    // the selection's blocks relative to its lowest corner, blocks that aren't loaded are left out
+    pub fn copy_selection(&self, chunk_manager: &ChunkManager) -> anyhow::Result<Structure> {
+        let block_box = self.selected_box()?;
+        let origin = block_box.min().block_position();
+        let chunk_map = chunk_manager.chunk_map_lock.read();
+        let blocks = block_box.iter()
+            .filter_map(|position| Some((position.block_position() - origin, chunk_map.get_block(position)?.clone())))
+            .collect();
+        Ok(Structure { blocks })
+    }
+
+    // sets the structure's blocks with its origin at the position
+    pub fn place_structure(&mut self, chunk_manager: &mut ChunkManager, structure: &Structure, origin: GlobalVecU) -> anyhow::Result<usize> {
+        if structure.blocks().len() as u64 > MAX_EDIT_VOLUME {
+            anyhow::bail!("the structure has {} blocks, at most {} can be placed at once", structure.blocks().len(), MAX_EDIT_VOLUME);
+        }
+        let mut batch = EditBatch::new();
+        for (position, block) in structure.blocks() {
+            batch.set(origin + *position, block);
+        }
+        Ok(self.apply(chunk_manager, &batch))
+    }
+}
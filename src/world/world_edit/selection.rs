@@ -0,0 +1,64 @@
+use cgmath::Vector3;
+
+use crate::global_vector::GlobalVecU;
+
+// The box of blocks between two corners, both corners are inside of it and it can span any number of chunks
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct BlockBox {
+    min: Vector3<i32>,
+    max: Vector3<i32>,
+}
+
+impl BlockBox {
+    pub fn new(first_corner: GlobalVecU, second_corner: GlobalVecU) -> Self {
+        let (first_corner, second_corner) = (first_corner.block_position(), second_corner.block_position());
+        Self {
+            min: first_corner.zip(second_corner, i32::min),
+            max: first_corner.zip(second_corner, i32::max),
+        }
+    }
+
+    #[inline]
+    pub fn min(&self) -> GlobalVecU {
+        GlobalVecU::from(self.min)
+    }
+
+    // the corners can be at the opposite ends of the i32 range, so it doesn't fit in an i32
+    #[inline]
+    pub fn size(&self) -> Vector3<u64> {
+        (self.max.map(|f| f as i64) - self.min.map(|f| f as i64)).map(|f| f as u64 + 1)
+    }
+
+    // saturates instead of overflowing for boxes that are far too big to edit
+    #[inline]
+    pub fn volume(&self) -> u64 {
+        let size = self.size();
+        size.x.saturating_mul(size.y).saturating_mul(size.z)
+    }
+
+    // whether the block is on one of the box's faces
+    #[inline]
+    pub fn is_on_surface(&self, position: GlobalVecU) -> bool {
+        let position = position.block_position();
+        (0..3).any(|axis| position[axis] == self.min[axis] || position[axis] == self.max[axis])
+    }
+
+    // the blocks layer by layer from the bottom
+    pub fn iter(&self) -> impl Iterator<Item = GlobalVecU> {
+        let (min, max) = (self.min, self.max);
+        (min.y..=max.y).flat_map(move |y| (min.z..=max.z).flat_map(move |z| (min.x..=max.x).map(move |x| GlobalVecU::from(Vector3::new(x, y, z)))))
+    }
+}
+
+// The two corners that are picked one after the other, the box between them is what the edits work on
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Selection {
+    pub first_corner: Option<GlobalVecU>,
+    pub second_corner: Option<GlobalVecU>,
+}
+
+impl Selection {
+    pub fn block_box(&self) -> Option<BlockBox> {
+        Some(BlockBox::new(self.first_corner?, self.second_corner?))
+    }
+}
@@ -0,0 +1,35 @@
+pub const DAY_LENGTH: u32 = 24000;
+pub const NOON: u32 = 6000;
+pub const MIDNIGHT: u32 = 18000;
+// the sky light is never dimmed below this fraction
+const MIN_DAYLIGHT: f32 = 0.2;
+
+// The time of day in ticks, 0 is sunrise and 12000 sunset. it doesn't advance on its own,
+// so the world stays at noon's full daylight until the time is set with the console
+#[derive(Debug, Clone, Copy)]
+pub struct WorldTime {
+    ticks: u32,
+}
+
+impl Default for WorldTime {
+    fn default() -> Self {
+        Self { ticks: NOON }
+    }
+}
+
+impl WorldTime {
+    #[inline]
+    pub fn ticks(&self) -> u32 {
+        self.ticks
+    }
+
+    pub fn set(&mut self, ticks: u32) {
+        self.ticks = ticks % DAY_LENGTH;
+    }
+
+    // 1 while the sun is up, falling to MIN_DAYLIGHT around sunset
+    pub fn daylight(&self) -> f32 {
+        let sun_height = (self.ticks as f32 / DAY_LENGTH as f32 * std::f32::consts::TAU).sin();
+        MIN_DAYLIGHT + (1.0 - MIN_DAYLIGHT) * (sun_height * 2.0 + 0.5).clamp(0.0, 1.0)
+    }
+}